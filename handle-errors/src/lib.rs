@@ -15,9 +15,35 @@ use tracing::{event, instrument, Level};
 pub enum Error {
     ParseError(std::num::ParseIntError),
     MissingParameters,
+    InvalidParameter(String),
+    UnsupportedMediaType,
     WrongPassword,
     ArgonLibraryError(ArgonError),
-    // QuestionNotFound,
+    QuestionNotFound,
+    RevisionNotFound,
+    AnswerNotFound,
+    CommentNotFound,
+    SuggestedEditNotFound,
+    BountyNotFound,
+    DraftNotFound,
+    AttachmentNotFound,
+    UserNotFound,
+    SessionNotFound,
+    QuestionClosed,
+    CannotVoteOnOwnPost,
+    CannotSuggestEditOnOwnPost,
+    SuggestedEditAlreadyReviewed,
+    BountyAlreadyOpen,
+    InsufficientPoints,
+    AttachmentTooLarge,
+    AttachmentQuotaExceeded,
+    StorageError(String),
+    InvalidResetToken,
+    InvalidVerificationToken,
+    EmailNotVerified,
+    EmailAlreadyVerified,
+    TooManyRequests,
+    MailError(String),
     DatabaseQueryError(sqlx::Error),
     MigrationError(sqlx::migrate::MigrateError),
     ReqwestAPIError(ReqwestError),
@@ -48,7 +74,39 @@ impl std::fmt::Display for Error {
                 write!(f, "Cannot parse parameter: {}", err)
             }
             Error::MissingParameters => write!(f, "Missing parameter."),
-            // Error::QuestionNotFound => write!(f, "Question not found."),
+            Error::InvalidParameter(ref err) => write!(f, "Invalid parameter: {}", err),
+            Error::UnsupportedMediaType => write!(f, "Unsupported content type."),
+            Error::QuestionNotFound => write!(f, "Question not found."),
+            Error::RevisionNotFound => write!(f, "Revision not found."),
+            Error::AnswerNotFound => write!(f, "Answer not found."),
+            Error::CommentNotFound => write!(f, "Comment not found."),
+            Error::SuggestedEditNotFound => write!(f, "Suggested edit not found."),
+            Error::BountyNotFound => write!(f, "No open bounty found."),
+            Error::DraftNotFound => write!(f, "Draft not found."),
+            Error::AttachmentNotFound => write!(f, "Attachment not found."),
+            Error::UserNotFound => write!(f, "User not found."),
+            Error::SessionNotFound => write!(f, "Session not found."),
+            Error::QuestionClosed => write!(f, "Question is closed for answers and edits."),
+            Error::CannotVoteOnOwnPost => write!(f, "Cannot vote on your own post."),
+            Error::CannotSuggestEditOnOwnPost => {
+                write!(f, "Cannot suggest edits to your own post, edit it instead.")
+            }
+            Error::SuggestedEditAlreadyReviewed => {
+                write!(f, "Suggested edit has already been reviewed.")
+            }
+            Error::BountyAlreadyOpen => write!(f, "Question already has an open bounty."),
+            Error::InsufficientPoints => write!(f, "Not enough points for this bounty."),
+            Error::AttachmentTooLarge => write!(f, "Attachment is too large."),
+            Error::AttachmentQuotaExceeded => write!(f, "Attachment quota exceeded."),
+            Error::StorageError(_) => write!(f, "Cannot access attachment storage."),
+            Error::InvalidResetToken => write!(f, "Reset token is invalid or has expired."),
+            Error::InvalidVerificationToken => {
+                write!(f, "Verification link is invalid or has expired.")
+            }
+            Error::EmailNotVerified => write!(f, "Verify your email before posting."),
+            Error::EmailAlreadyVerified => write!(f, "Email is already verified."),
+            Error::TooManyRequests => write!(f, "Too many requests, try again later."),
+            Error::MailError(_) => write!(f, "Cannot send email."),
             Error::WrongPassword => write!(f, "Wrong password."),
             Error::ArgonLibraryError(_) => write!(f, "Cannot verify password."),
             Error::DatabaseQueryError(_) => write!(f, "Cannot update, invalid data."),
@@ -108,6 +166,121 @@ pub async fn return_error(r: Rejection) -> Result<impl Reply, Rejection> {
             "No permission to change underlying resource".to_string(),
             StatusCode::UNAUTHORIZED,
         ))
+    } else if let Some(error) = r.find::<Error>().filter(|error| {
+        matches!(
+            error,
+            Error::QuestionNotFound
+                | Error::RevisionNotFound
+                | Error::AnswerNotFound
+                | Error::CommentNotFound
+                | Error::SuggestedEditNotFound
+                | Error::BountyNotFound
+                | Error::DraftNotFound
+                | Error::AttachmentNotFound
+                | Error::UserNotFound
+                | Error::SessionNotFound
+        )
+    }) {
+        event!(Level::WARN, "{}", error);
+        Ok(warp::reply::with_status(
+            error.to_string(),
+            StatusCode::NOT_FOUND,
+        ))
+    } else if let Some(crate::Error::QuestionClosed) = r.find() {
+        event!(Level::WARN, "Write on a question that is not open");
+        Ok(warp::reply::with_status(
+            crate::Error::QuestionClosed.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(crate::Error::CannotVoteOnOwnPost) = r.find() {
+        event!(Level::WARN, "Vote on own post");
+        Ok(warp::reply::with_status(
+            crate::Error::CannotVoteOnOwnPost.to_string(),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if let Some(crate::Error::CannotSuggestEditOnOwnPost) = r.find() {
+        event!(Level::WARN, "Suggested edit on own post");
+        Ok(warp::reply::with_status(
+            crate::Error::CannotSuggestEditOnOwnPost.to_string(),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if let Some(crate::Error::SuggestedEditAlreadyReviewed) = r.find() {
+        event!(
+            Level::WARN,
+            "Review of a suggested edit that is not pending"
+        );
+        Ok(warp::reply::with_status(
+            crate::Error::SuggestedEditAlreadyReviewed.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(error) = r
+        .find::<Error>()
+        .filter(|error| matches!(error, Error::BountyAlreadyOpen | Error::InsufficientPoints))
+    {
+        event!(Level::WARN, "{}", error);
+        Ok(warp::reply::with_status(
+            error.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(crate::Error::AttachmentTooLarge) = r.find() {
+        event!(Level::WARN, "Attachment over the size limit");
+        Ok(warp::reply::with_status(
+            crate::Error::AttachmentTooLarge.to_string(),
+            StatusCode::PAYLOAD_TOO_LARGE,
+        ))
+    } else if let Some(crate::Error::AttachmentQuotaExceeded) = r.find() {
+        event!(Level::WARN, "Attachment over the account quota");
+        Ok(warp::reply::with_status(
+            crate::Error::AttachmentQuotaExceeded.to_string(),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if let Some(crate::Error::StorageError(e)) = r.find() {
+        event!(Level::ERROR, "Storage error: {}", e);
+        Ok(warp::reply::with_status(
+            crate::Error::StorageError(e.clone()).to_string(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ))
+    } else if let Some(error) = r.find::<Error>().filter(|error| {
+        matches!(
+            error,
+            Error::InvalidResetToken | Error::InvalidVerificationToken
+        )
+    }) {
+        event!(Level::WARN, "{}", error);
+        Ok(warp::reply::with_status(
+            error.to_string(),
+            StatusCode::BAD_REQUEST,
+        ))
+    } else if let Some(crate::Error::EmailNotVerified) = r.find() {
+        event!(Level::WARN, "Post from an unverified account");
+        Ok(warp::reply::with_status(
+            crate::Error::EmailNotVerified.to_string(),
+            StatusCode::FORBIDDEN,
+        ))
+    } else if let Some(crate::Error::EmailAlreadyVerified) = r.find() {
+        event!(Level::WARN, "Verification resend for a verified account");
+        Ok(warp::reply::with_status(
+            crate::Error::EmailAlreadyVerified.to_string(),
+            StatusCode::CONFLICT,
+        ))
+    } else if let Some(crate::Error::TooManyRequests) = r.find() {
+        event!(Level::WARN, "Request throttled");
+        Ok(warp::reply::with_status(
+            crate::Error::TooManyRequests.to_string(),
+            StatusCode::TOO_MANY_REQUESTS,
+        ))
+    } else if let Some(crate::Error::MailError(e)) = r.find() {
+        event!(Level::ERROR, "Mail error: {}", e);
+        Ok(warp::reply::with_status(
+            crate::Error::MailError(e.clone()).to_string(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ))
+    } else if let Some(crate::Error::UnsupportedMediaType) = r.find() {
+        event!(Level::WARN, "Unsupported content type");
+        Ok(warp::reply::with_status(
+            crate::Error::UnsupportedMediaType.to_string(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        ))
     } else if let Some(crate::Error::WrongPassword) = r.find() {
         event!(Level::ERROR, "Entered wrong password.");
         Ok(warp::reply::with_status(
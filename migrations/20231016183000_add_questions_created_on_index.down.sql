@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS questions_created_on_id_idx;
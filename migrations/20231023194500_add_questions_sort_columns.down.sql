@@ -0,0 +1,12 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS questions_score_id_idx;
+DROP INDEX IF EXISTS questions_answer_count_id_idx;
+DROP INDEX IF EXISTS questions_last_activity_on_id_idx;
+
+DROP TRIGGER IF EXISTS answers_question_activity ON answers;
+DROP FUNCTION IF EXISTS update_question_activity();
+
+ALTER TABLE questions
+DROP COLUMN score,
+DROP COLUMN last_activity_on,
+DROP COLUMN answer_count;
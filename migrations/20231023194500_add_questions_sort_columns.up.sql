@@ -0,0 +1,42 @@
+-- Add up migration script here
+ALTER TABLE questions
+ADD COLUMN answer_count integer NOT NULL DEFAULT 0,
+ADD COLUMN last_activity_on TIMESTAMP NOT NULL DEFAULT NOW(),
+ADD COLUMN score integer NOT NULL DEFAULT 0;
+
+UPDATE questions
+SET answer_count = (
+        SELECT COUNT(*) FROM answers WHERE answers.corresponding_question = questions.id
+    ),
+    last_activity_on = GREATEST(
+        created_on,
+        COALESCE(
+            (SELECT MAX(created_on) FROM answers WHERE answers.corresponding_question = questions.id),
+            created_on
+        )
+    );
+
+CREATE OR REPLACE FUNCTION update_question_activity() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE questions
+        SET answer_count = answer_count + 1,
+            last_activity_on = GREATEST(last_activity_on, NEW.created_on)
+        WHERE id = NEW.corresponding_question;
+        RETURN NEW;
+    ELSE
+        UPDATE questions
+        SET answer_count = answer_count - 1
+        WHERE id = OLD.corresponding_question;
+        RETURN OLD;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER answers_question_activity
+AFTER INSERT OR DELETE ON answers
+FOR EACH ROW EXECUTE FUNCTION update_question_activity();
+
+CREATE INDEX IF NOT EXISTS questions_last_activity_on_id_idx ON questions (last_activity_on, id);
+CREATE INDEX IF NOT EXISTS questions_answer_count_id_idx ON questions (answer_count, id);
+CREATE INDEX IF NOT EXISTS questions_score_id_idx ON questions (score, id);
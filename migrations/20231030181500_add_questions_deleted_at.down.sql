@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS questions_deleted_at_idx;
+
+ALTER TABLE questions
+DROP COLUMN deleted_at;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS question_revisions (
+    id serial PRIMARY KEY,
+    question_id integer NOT NULL REFERENCES questions ON DELETE CASCADE,
+    title VARCHAR (255) NOT NULL,
+    content TEXT NOT NULL,
+    tags TEXT [],
+    account_id integer NOT NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS question_revisions_question_id_idx ON question_revisions (question_id, id);
+
+-- Existing questions start their history with their current state
+INSERT INTO question_revisions (question_id, title, content, tags, account_id, created_on)
+SELECT id, title, content, tags, account_id, created_on FROM questions;
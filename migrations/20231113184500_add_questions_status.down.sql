@@ -0,0 +1,5 @@
+-- Add down migration script here
+ALTER TABLE questions
+DROP COLUMN duplicate_of,
+DROP COLUMN close_reason,
+DROP COLUMN status;
@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE questions
+ADD COLUMN status VARCHAR (16) NOT NULL DEFAULT 'open'
+    CHECK (status IN ('open', 'closed', 'duplicate', 'locked')),
+ADD COLUMN close_reason TEXT,
+ADD COLUMN duplicate_of integer REFERENCES questions ON DELETE SET NULL;
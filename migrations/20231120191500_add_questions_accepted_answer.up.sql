@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE questions
+ADD COLUMN accepted_answer_id integer REFERENCES answers ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS questions_accepted_answer_id_idx ON questions (accepted_answer_id)
+WHERE accepted_answer_id IS NOT NULL;
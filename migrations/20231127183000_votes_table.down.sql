@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS votes_post_score ON votes;
+DROP FUNCTION IF EXISTS update_post_score();
+DROP TABLE IF EXISTS votes;
+
+UPDATE questions SET score = 0;
+
+ALTER TABLE answers
+DROP COLUMN score;
@@ -0,0 +1,37 @@
+-- Add up migration script here
+ALTER TABLE answers
+ADD COLUMN score integer NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS votes (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    question_id integer REFERENCES questions ON DELETE CASCADE,
+    answer_id integer REFERENCES answers ON DELETE CASCADE,
+    value smallint NOT NULL CHECK (value IN (-1, 1)),
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((question_id IS NULL) <> (answer_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS votes_account_question_idx ON votes (account_id, question_id)
+WHERE question_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS votes_account_answer_idx ON votes (account_id, answer_id)
+WHERE answer_id IS NOT NULL;
+
+-- Keeps questions.score and answers.score equal to the sum of their votes
+CREATE OR REPLACE FUNCTION update_post_score() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE questions SET score = score - OLD.value WHERE id = OLD.question_id;
+        UPDATE answers SET score = score - OLD.value WHERE id = OLD.answer_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE questions SET score = score + NEW.value WHERE id = NEW.question_id;
+        UPDATE answers SET score = score + NEW.value WHERE id = NEW.answer_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER votes_post_score
+AFTER INSERT OR UPDATE OR DELETE ON votes
+FOR EACH ROW EXECUTE FUNCTION update_post_score();
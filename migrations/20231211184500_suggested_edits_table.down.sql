@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS contributors;
+DROP TABLE IF EXISTS suggested_edits;
@@ -0,0 +1,34 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS suggested_edits (
+    id serial PRIMARY KEY,
+    question_id integer REFERENCES questions ON DELETE CASCADE,
+    answer_id integer REFERENCES answers ON DELETE CASCADE,
+    account_id integer NOT NULL,
+    title VARCHAR (255),
+    content TEXT NOT NULL,
+    tags TEXT [],
+    status VARCHAR (16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected')),
+    reviewer_id integer,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    reviewed_on TIMESTAMP,
+    CHECK ((question_id IS NULL) <> (answer_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS suggested_edits_pending_idx ON suggested_edits (created_on, id)
+WHERE status = 'pending';
+
+-- Accounts whose suggested edits were applied to a post
+CREATE TABLE IF NOT EXISTS contributors (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    question_id integer REFERENCES questions ON DELETE CASCADE,
+    answer_id integer REFERENCES answers ON DELETE CASCADE,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((question_id IS NULL) <> (answer_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS contributors_question_account_idx ON contributors (question_id, account_id)
+WHERE question_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS contributors_answer_account_idx ON contributors (answer_id, account_id)
+WHERE answer_id IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS accounts_initial_points ON accounts;
+DROP FUNCTION IF EXISTS grant_initial_points();
+DROP TRIGGER IF EXISTS points_ledger_account_points ON points_ledger;
+DROP FUNCTION IF EXISTS update_account_points();
+DROP TABLE IF EXISTS points_ledger;
+DROP TABLE IF EXISTS bounties;
+
+ALTER TABLE accounts
+DROP COLUMN points;
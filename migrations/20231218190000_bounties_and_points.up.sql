@@ -0,0 +1,60 @@
+-- Add up migration script here
+ALTER TABLE accounts
+ADD COLUMN points integer NOT NULL DEFAULT 0 CHECK (points >= 0);
+
+CREATE TABLE IF NOT EXISTS bounties (
+    id serial PRIMARY KEY,
+    question_id integer NOT NULL REFERENCES questions ON DELETE CASCADE,
+    account_id integer NOT NULL,
+    amount integer NOT NULL CHECK (amount > 0),
+    status VARCHAR (16) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'awarded', 'expired')),
+    awarded_answer_id integer REFERENCES answers ON DELETE SET NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_on TIMESTAMP NOT NULL,
+    closed_on TIMESTAMP
+);
+
+-- A question can only have one open bounty at a time
+CREATE UNIQUE INDEX IF NOT EXISTS bounties_open_question_idx ON bounties (question_id)
+WHERE status = 'open';
+CREATE INDEX IF NOT EXISTS bounties_open_expires_on_idx ON bounties (expires_on)
+WHERE status = 'open';
+
+-- Every change to the points of an account, accounts.points is their sum
+CREATE TABLE IF NOT EXISTS points_ledger (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    amount integer NOT NULL,
+    reason VARCHAR (32) NOT NULL,
+    bounty_id integer REFERENCES bounties ON DELETE SET NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS points_ledger_account_id_idx ON points_ledger (account_id, id);
+
+CREATE OR REPLACE FUNCTION update_account_points() RETURNS trigger AS $$
+BEGIN
+    UPDATE accounts SET points = points + NEW.amount WHERE id = NEW.account_id;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER points_ledger_account_points
+AFTER INSERT ON points_ledger
+FOR EACH ROW EXECUTE FUNCTION update_account_points();
+
+-- New accounts start with some points so they can offer a first bounty
+CREATE OR REPLACE FUNCTION grant_initial_points() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO points_ledger (account_id, amount, reason) VALUES (NEW.id, 100, 'initial_grant');
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER accounts_initial_points
+AFTER INSERT ON accounts
+FOR EACH ROW EXECUTE FUNCTION grant_initial_points();
+
+INSERT INTO points_ledger (account_id, amount, reason)
+SELECT id, 100, 'initial_grant' FROM accounts;
@@ -0,0 +1,15 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS reactions (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    question_id integer REFERENCES questions ON DELETE CASCADE,
+    answer_id integer REFERENCES answers ON DELETE CASCADE,
+    reaction VARCHAR (16) NOT NULL CHECK (reaction IN ('thanks', '+1', 'eyes', 'confused')),
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((question_id IS NULL) <> (answer_id IS NULL))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS reactions_account_question_idx ON reactions (question_id, account_id, reaction)
+WHERE question_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS reactions_account_answer_idx ON reactions (answer_id, account_id, reaction)
+WHERE answer_id IS NOT NULL;
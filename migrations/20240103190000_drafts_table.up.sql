@@ -0,0 +1,12 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS drafts (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    title VARCHAR (255),
+    content TEXT,
+    tags TEXT [],
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    updated_on TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS drafts_account_id_idx ON drafts (account_id, updated_on);
@@ -0,0 +1,19 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS attachments (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    question_id integer REFERENCES questions ON DELETE CASCADE,
+    answer_id integer REFERENCES answers ON DELETE CASCADE,
+    storage_key VARCHAR (255) NOT NULL UNIQUE,
+    file_name VARCHAR (255) NOT NULL,
+    content_type VARCHAR (255) NOT NULL,
+    size bigint NOT NULL,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((question_id IS NULL) <> (answer_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS attachments_question_id_idx ON attachments (question_id)
+WHERE question_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS attachments_answer_id_idx ON attachments (answer_id)
+WHERE answer_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS attachments_account_id_idx ON attachments (account_id);
@@ -0,0 +1,9 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS answers_account_id_idx;
+DROP INDEX IF EXISTS questions_account_id_idx;
+
+ALTER TABLE accounts
+DROP COLUMN display_name,
+DROP COLUMN bio,
+DROP COLUMN avatar_url,
+DROP COLUMN created_on;
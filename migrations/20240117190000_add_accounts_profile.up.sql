@@ -0,0 +1,9 @@
+-- Add up migration script here
+ALTER TABLE accounts
+ADD COLUMN display_name VARCHAR (64),
+ADD COLUMN bio TEXT,
+ADD COLUMN avatar_url VARCHAR (2048),
+ADD COLUMN created_on TIMESTAMP NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS questions_account_id_idx ON questions (account_id, created_on);
+CREATE INDEX IF NOT EXISTS answers_account_id_idx ON answers (account_id, created_on);
@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS password_resets (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    -- SHA-256 of the token that was emailed, the token itself is never stored
+    token_hash VARCHAR (64) NOT NULL UNIQUE,
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_on TIMESTAMP NOT NULL,
+    used_on TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS password_resets_account_id_idx ON password_resets (account_id)
+WHERE used_on IS NULL;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE accounts
+DROP COLUMN verification_sent_on,
+DROP COLUMN verified_on;
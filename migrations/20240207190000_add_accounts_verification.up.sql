@@ -0,0 +1,7 @@
+-- Add up migration script here
+ALTER TABLE accounts
+ADD COLUMN verified_on TIMESTAMP,
+ADD COLUMN verification_sent_on TIMESTAMP;
+
+-- Accounts from before verification existed keep being able to post
+UPDATE accounts SET verified_on = created_on;
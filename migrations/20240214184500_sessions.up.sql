@@ -0,0 +1,24 @@
+-- Add up migration script here
+CREATE TABLE IF NOT EXISTS sessions (
+    id serial PRIMARY KEY,
+    account_id integer NOT NULL,
+    -- SHA-256 of the current refresh token, replaced on every refresh
+    refresh_token_hash VARCHAR (64) NOT NULL UNIQUE,
+    -- jti of the latest access token issued for the session
+    access_jti VARCHAR (36) NOT NULL,
+    user_agent VARCHAR (255),
+    created_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_used_on TIMESTAMP NOT NULL DEFAULT NOW(),
+    expires_on TIMESTAMP NOT NULL,
+    revoked_on TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS sessions_account_id_idx ON sessions (account_id)
+WHERE revoked_on IS NULL;
+CREATE INDEX IF NOT EXISTS sessions_access_jti_idx ON sessions (access_jti);
+
+-- Access tokens that were revoked before they expired, checked on every request
+CREATE TABLE IF NOT EXISTS revoked_tokens (
+    jti VARCHAR (36) PRIMARY KEY,
+    expires_on TIMESTAMP NOT NULL
+);
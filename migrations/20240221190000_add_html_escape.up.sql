@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- Escapes user content before search results wrap matches in <mark> tags
+CREATE OR REPLACE FUNCTION html_escape(text) RETURNS text AS $$
+    SELECT replace(replace(replace(replace(replace($1,
+        '&', '&amp;'), '<', '&lt;'), '>', '&gt;'), '"', '&quot;'), '''', '&#39;')
+$$ LANGUAGE SQL IMMUTABLE STRICT;
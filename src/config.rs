@@ -27,6 +27,60 @@ pub struct Config {
     /// Database naem
     #[clap(long, default_value = "rustwebdev")]
     pub db_name: String,
+    /// Days a deleted question stays in the trash before it is purged
+    #[clap(long, default_value = "30")]
+    pub trash_retention_days: i32,
+    /// Where attachments are stored, `local` or `s3`
+    #[clap(long, default_value = "local")]
+    pub storage_backend: String,
+    /// Directory for attachments with the local storage backend
+    #[clap(long, default_value = "attachments")]
+    pub storage_path: String,
+    /// Bucket for attachments with the s3 storage backend
+    #[clap(long, default_value = "attachments")]
+    pub s3_bucket: String,
+    /// Region of the s3 bucket
+    #[clap(long, default_value = "us-east-1")]
+    pub s3_region: String,
+    /// Url of the S3 compatible API, e.g. a local MinIO
+    #[clap(long, default_value = "http://localhost:9000")]
+    pub s3_endpoint: String,
+    /// Access key for the S3 compatible API
+    #[clap(long, default_value = "")]
+    pub s3_access_key: String,
+    /// Secret key for the S3 compatible API
+    #[clap(long, default_value = "")]
+    pub s3_secret_key: String,
+    /// Largest attachment in bytes
+    #[clap(long, default_value = "10485760")]
+    pub max_attachment_size: u64,
+    /// Total bytes of attachments a single account can upload
+    #[clap(long, default_value = "104857600")]
+    pub attachment_quota: i64,
+    /// How emails are delivered, `smtp` or `file`
+    #[clap(long, default_value = "smtp")]
+    pub mailer: String,
+    /// SMTP server, e.g. a local MailHog
+    #[clap(long, default_value = "localhost")]
+    pub smtp_host: String,
+    /// PORT of the SMTP server
+    #[clap(long, default_value = "1025")]
+    pub smtp_port: u16,
+    /// SMTP user, connecting over TLS when set and without TLS or authentication when empty
+    #[clap(long, default_value = "")]
+    pub smtp_username: String,
+    /// SMTP password
+    #[clap(long, default_value = "")]
+    pub smtp_password: String,
+    /// Sender address of emails
+    #[clap(long, default_value = "Q&A <noreply@localhost>")]
+    pub mail_from: String,
+    /// Directory for emails with the file mailer
+    #[clap(long, default_value = "mails")]
+    pub mail_path: String,
+    /// Url the API is reachable at, used for links in emails
+    #[clap(long, default_value = "http://localhost:8080")]
+    pub public_url: String,
     // Web server port
     // port: u16,
 }
@@ -37,11 +91,11 @@ impl Config {
 
         let config = Config::parse();
 
-        if let Err(_) = env::var("BAD_WORDS_API_KEY") {
+        if env::var("BAD_WORDS_API_KEY").is_err() {
             panic!("BadWords API key not set.");
         }
 
-        if let Err(_) = env::var("PASETO_KEY") {
+        if env::var("PASETO_KEYS").is_err() && env::var("PASETO_KEY").is_err() {
             panic!("Paseto key not set.");
         }
 
@@ -49,13 +103,49 @@ impl Config {
             .ok()
             .map(|val| val.parse::<u16>())
             .unwrap_or(Ok(8080))
-            .map_err(|e| handle_errors::Error::ParseError(e))?;
+            .map_err(handle_errors::Error::ParseError)?;
 
         let db_user = env::var("POSTGRES_USER").unwrap_or(config.db_user.to_owned());
         let db_password = env::var("POSTGRES_PASSWORD").unwrap();
         let db_host = env::var("POSTGRES_HOST").unwrap_or(config.db_host.to_owned());
         let db_port = env::var("POSTGRES_PORT").unwrap_or(config.db_port.to_string());
         let db_name = env::var("POSTGRES_DB").unwrap_or(config.db_name.to_owned());
+        let trash_retention_days = env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .map(|val| val.parse::<i32>())
+            .unwrap_or(Ok(config.trash_retention_days))
+            .map_err(handle_errors::Error::ParseError)?;
+
+        let storage_backend = env::var("STORAGE_BACKEND").unwrap_or(config.storage_backend);
+        let storage_path = env::var("STORAGE_PATH").unwrap_or(config.storage_path);
+        let s3_bucket = env::var("S3_BUCKET").unwrap_or(config.s3_bucket);
+        let s3_region = env::var("S3_REGION").unwrap_or(config.s3_region);
+        let s3_endpoint = env::var("S3_ENDPOINT").unwrap_or(config.s3_endpoint);
+        let s3_access_key = env::var("S3_ACCESS_KEY").unwrap_or(config.s3_access_key);
+        let s3_secret_key = env::var("S3_SECRET_KEY").unwrap_or(config.s3_secret_key);
+        let max_attachment_size = env::var("MAX_ATTACHMENT_SIZE")
+            .ok()
+            .map(|val| val.parse::<u64>())
+            .unwrap_or(Ok(config.max_attachment_size))
+            .map_err(handle_errors::Error::ParseError)?;
+        let attachment_quota = env::var("ATTACHMENT_QUOTA")
+            .ok()
+            .map(|val| val.parse::<i64>())
+            .unwrap_or(Ok(config.attachment_quota))
+            .map_err(handle_errors::Error::ParseError)?;
+
+        let mailer = env::var("MAILER").unwrap_or(config.mailer);
+        let smtp_host = env::var("SMTP_HOST").unwrap_or(config.smtp_host);
+        let smtp_port = env::var("SMTP_PORT")
+            .ok()
+            .map(|val| val.parse::<u16>())
+            .unwrap_or(Ok(config.smtp_port))
+            .map_err(handle_errors::Error::ParseError)?;
+        let smtp_username = env::var("SMTP_USERNAME").unwrap_or(config.smtp_username);
+        let smtp_password = env::var("SMTP_PASSWORD").unwrap_or(config.smtp_password);
+        let mail_from = env::var("MAIL_FROM").unwrap_or(config.mail_from);
+        let mail_path = env::var("MAIL_PATH").unwrap_or(config.mail_path);
+        let public_url = env::var("PUBLIC_URL").unwrap_or(config.public_url);
 
         Ok(Config {
             log_level: config.log_level,
@@ -65,8 +155,26 @@ impl Config {
             db_host,
             db_port: db_port
                 .parse::<u16>()
-                .map_err(|e| handle_errors::Error::ParseError(e))?,
+                .map_err(handle_errors::Error::ParseError)?,
             db_name,
+            trash_retention_days,
+            storage_backend,
+            storage_path,
+            s3_bucket,
+            s3_region,
+            s3_endpoint,
+            s3_access_key,
+            s3_secret_key,
+            max_attachment_size,
+            attachment_quota,
+            mailer,
+            smtp_host,
+            smtp_port,
+            smtp_username,
+            smtp_password,
+            mail_from,
+            mail_path,
+            public_url,
         })
     }
 }
@@ -77,7 +185,7 @@ mod config_tests {
 
     fn set_env() {
         env::set_var("BAD_WORDS_API_KEY", "yes");
-        // env::set_var("PASETO_KEY", "yes");
+        env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
         env::set_var("POSTGRES_USER", "user");
         env::set_var("POSTGRES_PASSWORD", "pass");
         env::set_var("POSTGRES_HOST", "localhost");
@@ -94,7 +202,7 @@ mod config_tests {
     #[test]
     fn unset_and_set_api_key() {
         // ENV VARIABLES ARE NOT SET
-        let result = std::panic::catch_unwind(|| Config::new());
+        let result = std::panic::catch_unwind(Config::new);
         assert!(result.is_err());
 
         // NOW SET SET ENV VARIABLES
@@ -108,6 +216,24 @@ mod config_tests {
             db_host: "localhost".to_string(),
             db_port: 5432,
             db_name: "rustwebdev".to_string(),
+            trash_retention_days: 30,
+            storage_backend: "local".to_string(),
+            storage_path: "attachments".to_string(),
+            s3_bucket: "attachments".to_string(),
+            s3_region: "us-east-1".to_string(),
+            s3_endpoint: "http://localhost:9000".to_string(),
+            s3_access_key: "".to_string(),
+            s3_secret_key: "".to_string(),
+            max_attachment_size: 10485760,
+            attachment_quota: 104857600,
+            mailer: "smtp".to_string(),
+            smtp_host: "localhost".to_string(),
+            smtp_port: 1025,
+            smtp_username: "".to_string(),
+            smtp_password: "".to_string(),
+            mail_from: "Q&A <noreply@localhost>".to_string(),
+            mail_path: "mails".to_string(),
+            public_url: "http://localhost:8080".to_string(),
         };
 
         let config = Config::new().unwrap();
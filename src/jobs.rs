@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use tracing::{event, Level};
+
+use crate::routes::authentication::set_revoked_tokens;
+use crate::storage::FileStore;
+use crate::store::Store;
+
+/// How often the background jobs check for work
+const JOB_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// How long a token revoked on another instance can still be used here
+const REVOCATION_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Permanently removes questions that have been in the trash for longer than `retention_days`,
+/// together with the files of their attachments
+pub async fn purge_trash(store: Store, files: FileStore, retention_days: i32) {
+    let mut interval = tokio::time::interval(JOB_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match store.clone().purge_deleted_questions(retention_days).await {
+            Ok((purged, storage_keys)) => {
+                files.delete_files(&storage_keys).await;
+                event!(Level::INFO, purged, "purged trashed questions")
+            }
+            Err(e) => event!(Level::ERROR, "Cannot purge trashed questions: {}", e),
+        }
+    }
+}
+
+/// Pays out bounties that ran out to the highest scored answer of their question
+pub async fn expire_bounties(store: Store) {
+    let mut interval = tokio::time::interval(JOB_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let bounties = match store.clone().get_expired_bounties().await {
+            Ok(bounties) => bounties,
+            Err(e) => {
+                event!(Level::ERROR, "Cannot load expired bounties: {}", e);
+                continue;
+            }
+        };
+
+        for bounty in bounties {
+            match store.clone().expire_bounty(&bounty).await {
+                Ok(bounty) => event!(
+                    Level::INFO,
+                    bounty_id = bounty.id.0,
+                    status = ?bounty.status,
+                    "closed expired bounty"
+                ),
+                Err(e) => event!(
+                    Level::ERROR,
+                    bounty_id = bounty.id.0,
+                    "Cannot close expired bounty: {}",
+                    e
+                ),
+            }
+        }
+    }
+}
+
+/// Reloads the revocation list `verify_token` checks, dropping tokens that expired meanwhile
+pub async fn sync_revoked_tokens(store: Store) {
+    let mut interval = tokio::time::interval(REVOCATION_SYNC_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match store.clone().get_revoked_tokens().await {
+            Ok(jtis) => set_revoked_tokens(jtis.into_iter().collect()),
+            Err(e) => event!(Level::ERROR, "Cannot load revoked tokens: {}", e),
+        }
+    }
+}
+
+/// Removes sessions and revoked tokens that expired
+pub async fn purge_sessions(store: Store) {
+    let mut interval = tokio::time::interval(JOB_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match store.clone().purge_expired_sessions().await {
+            Ok(purged) => event!(Level::INFO, purged, "purged expired sessions"),
+            Err(e) => event!(Level::ERROR, "Cannot purge expired sessions: {}", e),
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use std::env;
+use std::sync::OnceLock;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::prelude::*;
+use paseto::tokens::{PasetoBuilder, PasetoPublicKey, TimeBackend};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use handle_errors::Error;
+
+/// Length of `v2.local` keys and Ed25519 seeds in bytes
+const KEY_LENGTH: usize = 32;
+
+static KEYRING: OnceLock<Keyring> = OnceLock::new();
+
+/// Loads the keyring from the environment and fails on invalid keys, meant to run at startup
+pub fn init() -> Result<(), Error> {
+    let keyring = Keyring::from_env()?;
+    let _ = KEYRING.set(keyring);
+    Ok(())
+}
+
+/// The keyring tokens are issued and verified with, loaded on first use if `init` did not run
+pub fn keyring() -> &'static Keyring {
+    KEYRING.get_or_init(|| Keyring::from_env().expect("Invalid PASETO keys"))
+}
+
+/// Footer of every token, names the key it was issued with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct Footer {
+    kid: String,
+}
+
+/// Public part of a signing key, for other services to verify `v2.public` tokens
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PublicKey {
+    pub kid: String,
+    pub version: &'static str,
+    pub purpose: &'static str,
+    /// Base64url encoded Ed25519 public key
+    pub public_key: String,
+}
+
+/// All keys tokens can be verified with, each named by a key id (kid).
+/// New tokens are issued with the first key of the list, so a key is rotated by putting
+/// a new one in front and dropping the old one once its tokens expired.
+#[derive(Debug)]
+pub struct Keyring {
+    /// `v2.local` keys, the first one is current
+    local: Vec<(String, Vec<u8>)>,
+    /// Ed25519 key pairs, when set new tokens are `v2.public` and signed with the first one
+    public: Vec<(String, Ed25519KeyPair)>,
+}
+
+impl Keyring {
+    /// Reads `PASETO_KEYS` as `kid:key,kid:key` with base64url encoded 32 byte keys, falling
+    /// back to a single raw 32 character `PASETO_KEY` with the kid `default`.
+    /// `PASETO_PUBLIC_KEYS` optionally holds `kid:seed` pairs with base64url encoded 32 byte
+    /// Ed25519 seeds.
+    pub fn from_env() -> Result<Self, Error> {
+        let local = match env::var("PASETO_KEYS") {
+            Ok(keys) => parse_keys(&keys)?,
+            Err(_) => {
+                let key = env::var("PASETO_KEY").map_err(|_| {
+                    Error::InvalidParameter(String::from("PASETO_KEYS or PASETO_KEY must be set"))
+                })?;
+                vec![(String::from("default"), key.into_bytes())]
+            }
+        };
+
+        let public = match env::var("PASETO_PUBLIC_KEYS") {
+            Ok(keys) if !keys.is_empty() => parse_keys(&keys)?,
+            _ => Vec::new(),
+        };
+
+        Keyring::new(local, public)
+    }
+
+    pub fn new(
+        local: Vec<(String, Vec<u8>)>,
+        public: Vec<(String, Vec<u8>)>,
+    ) -> Result<Self, Error> {
+        if local.is_empty() {
+            return Err(Error::InvalidParameter(String::from(
+                "at least one local PASETO key is needed",
+            )));
+        }
+        for (kid, key) in local.iter().chain(public.iter()) {
+            if key.len() != KEY_LENGTH {
+                return Err(Error::InvalidParameter(format!(
+                    "PASETO key '{}' must be {} bytes long, got {}",
+                    kid,
+                    KEY_LENGTH,
+                    key.len()
+                )));
+            }
+        }
+
+        let public = public
+            .into_iter()
+            .map(|(kid, seed)| {
+                Ed25519KeyPair::from_seed_unchecked(&seed)
+                    .map(|key_pair| (kid.clone(), key_pair))
+                    .map_err(|_| Error::InvalidParameter(format!("invalid public key '{}'", kid)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Keyring { local, public })
+    }
+
+    /// A keyring of `v2.local` keys derived from these ones for `context`, so tokens made for
+    /// one purpose are never accepted for another. Rotates together with this keyring.
+    pub fn derive(&self, context: &str) -> Keyring {
+        let local = self
+            .local
+            .iter()
+            .map(|(kid, key)| {
+                let derived = Sha256::new()
+                    .chain_update(context.as_bytes())
+                    .chain_update(key)
+                    .finalize()
+                    .to_vec();
+                (kid.clone(), derived)
+            })
+            .collect();
+
+        Keyring {
+            local,
+            public: Vec::new(),
+        }
+    }
+
+    /// Issues a token with the current key, `v2.public` if there are public keys
+    pub fn issue(&self, claims: &[(&str, Value)], expiration: &DateTime<Utc>) -> String {
+        let now = Utc::now();
+        let kid = match self.public.first() {
+            Some((kid, _)) => kid,
+            None => &self.local[0].0,
+        };
+        let footer = serde_json::to_string(&Footer { kid: kid.clone() })
+            .expect("Failed to serialize token footer.");
+
+        let mut builder = PasetoBuilder::new();
+        let builder = claims
+            .iter()
+            .fold(&mut builder, |builder, (name, value)| {
+                builder.set_claim(name, value.clone())
+            })
+            .set_expiration(expiration)
+            .set_not_before(&now)
+            .set_footer(&footer);
+        let builder = match self.public.first() {
+            Some((_, key_pair)) => builder.set_ed25519_key(key_pair),
+            None => builder.set_encryption_key(&self.local[0].1),
+        };
+
+        builder
+            .build()
+            .expect("Failed to construct paseto token w/ builder.")
+    }
+
+    /// Checks the token against the key named in its footer and returns its claims
+    pub fn verify(&self, token: &str) -> Result<Value, Error> {
+        let footer = token
+            .split('.')
+            .nth(3)
+            .and_then(|footer| URL_SAFE_NO_PAD.decode(footer).ok())
+            .and_then(|footer| String::from_utf8(footer).ok())
+            .ok_or(Error::CannotDecryptToken)?;
+        let kid = serde_json::from_str::<Footer>(&footer)
+            .map_err(|_| Error::CannotDecryptToken)?
+            .kid;
+
+        let claims = if token.starts_with("v2.public.") {
+            let (_, key_pair) = self
+                .public
+                .iter()
+                .find(|(id, _)| *id == kid)
+                .ok_or(Error::CannotDecryptToken)?;
+            paseto::tokens::validate_public_token(
+                token,
+                Some(&footer),
+                &PasetoPublicKey::ED25519KeyPair(key_pair),
+                &TimeBackend::Chrono,
+            )
+        } else {
+            let (_, key) = self
+                .local
+                .iter()
+                .find(|(id, _)| *id == kid)
+                .ok_or(Error::CannotDecryptToken)?;
+            paseto::tokens::validate_local_token(token, Some(&footer), key, &TimeBackend::Chrono)
+        };
+
+        claims.map_err(|_| Error::CannotDecryptToken)
+    }
+
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.public
+            .iter()
+            .map(|(kid, key_pair)| PublicKey {
+                kid: kid.clone(),
+                version: "v2",
+                purpose: "public",
+                public_key: URL_SAFE_NO_PAD.encode(key_pair.public_key().as_ref()),
+            })
+            .collect()
+    }
+}
+
+/// Splits `kid:key,kid:key` and decodes the base64url keys, keeping the order
+fn parse_keys(keys: &str) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    keys.split(',')
+        .map(|entry| match entry.trim().split_once(':') {
+            Some((kid, key)) if !kid.is_empty() && !key.is_empty() => URL_SAFE_NO_PAD
+                .decode(key)
+                .map(|key| (kid.to_string(), key))
+                .map_err(|_| Error::InvalidParameter(format!("key '{}' is not base64url", kid))),
+            _ => Err(Error::InvalidParameter(String::from(
+                "PASETO keys must be given as kid:key,kid:key",
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod keyring_tests {
+    use super::{parse_keys, Keyring};
+    use chrono::prelude::*;
+
+    fn claims() -> Vec<(&'static str, serde_json::Value)> {
+        vec![("account_id", serde_json::json!(3))]
+    }
+
+    fn expiration() -> DateTime<Utc> {
+        Utc::now() + chrono::Duration::minutes(5)
+    }
+
+    fn local(kids: &[&str]) -> Keyring {
+        let keys = kids
+            .iter()
+            .map(|kid| (kid.to_string(), format!("{:0<32}", kid).into_bytes()))
+            .collect();
+        Keyring::new(keys, Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn rotation() {
+        let old = local(&["old"]);
+        let rotated = local(&["new", "old"]);
+        let token = old.issue(&claims(), &expiration());
+
+        assert_eq!(rotated.verify(&token).unwrap()["account_id"], 3);
+        assert!(local(&["new"]).verify(&token).is_err());
+
+        let token = rotated.issue(&claims(), &expiration());
+        assert!(old.verify(&token).is_err());
+        assert!(rotated.verify(&token).is_ok());
+    }
+
+    #[test]
+    fn derived_keys_differ() {
+        let keyring = local(&["a"]);
+        let token = keyring.derive("email").issue(&claims(), &expiration());
+
+        assert!(keyring.derive("email").verify(&token).is_ok());
+        assert!(keyring.verify(&token).is_err());
+    }
+
+    #[test]
+    fn public_tokens() {
+        let keyring = Keyring::new(
+            vec![("a".to_string(), vec![1; 32])],
+            vec![("p".to_string(), vec![7; 32])],
+        )
+        .unwrap();
+        let token = keyring.issue(&claims(), &expiration());
+
+        assert!(token.starts_with("v2.public."));
+        assert_eq!(keyring.verify(&token).unwrap()["account_id"], 3);
+        assert_eq!(keyring.public_keys()[0].kid, "p");
+    }
+
+    #[test]
+    fn key_length() {
+        assert!(Keyring::new(vec![("a".to_string(), vec![1; 16])], Vec::new()).is_err());
+        assert!(Keyring::new(Vec::new(), Vec::new()).is_err());
+        assert!(parse_keys("a:a2V5,b").is_err());
+        assert!(parse_keys("a:not base64").is_err());
+        let keys = parse_keys("a:eA, b:eQ").unwrap();
+        assert_eq!(keys[1], ("b".to_string(), b"y".to_vec()));
+    }
+}
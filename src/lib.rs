@@ -1,16 +1,23 @@
 #![warn(clippy::all)]
+// The route filters nest deeper than the default limit
+#![recursion_limit = "256"]
 // use clap::Parser;
 // use config::Config;
 // use dotenv;
 pub use handle_errors;
 // use std::env;
+use std::sync::Arc;
 use tokio::sync::{oneshot, oneshot::Sender};
 use tracing_subscriber::fmt::format::FmtSpan;
 use warp::{http::Method, Filter, Reply};
 
 pub mod config;
+mod jobs;
+mod keyring;
+pub mod mailer;
 mod profanity;
 mod routes;
+mod storage;
 mod store;
 pub mod types;
 
@@ -219,24 +226,55 @@ pub mod types;
 
 pub struct OneshotHandler {
     pub sender: Sender<i32>,
+    /// Emails the test server sent, e.g. password reset tokens
+    pub mailer: mailer::MemoryMailer,
 }
 
-async fn build_routes(store: store::Store) -> impl Filter<Extract = impl Reply> + Clone {
+async fn build_routes(
+    store: store::Store,
+    files: storage::FileStore,
+    mailer: Arc<dyn mailer::Mailer>,
+    public_url: String,
+) -> impl Filter<Extract = impl Reply> + Clone {
+    // Posting needs a verified email, reading, voting and editing only a login
+    let verified = routes::authentication::auth_verified(store.clone());
     let store_filter = warp::any().map(move || store.clone());
+    // Leaves room for the multipart boundaries and headers around the file itself
+    let max_form_size = files.max_file_size + 64 * 1024;
+    let files_filter = warp::any().map(move || files.clone());
+    let mailer_filter = warp::any().map(move || mailer.clone());
+    let public_url_filter = warp::any().map(move || public_url.clone());
 
     let cors = warp::cors()
         .allow_any_origin()
         .allow_header("content-type")
-        .allow_methods(&[Method::PUT, Method::DELETE]);
+        .allow_methods(&[Method::PUT, Method::PATCH, Method::DELETE])
+        .expose_headers(vec!["link", "location"]);
 
     let get_questions = warp::get()
         .and(warp::path("questions"))
         .and(warp::path::end())
         .and(warp::query())
+        .and(routes::authentication::optional_auth())
         .and(store_filter.clone())
         // .and(id_filter)  LOGGING
         .and_then(routes::question::get_questions);
 
+    let get_question = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::question::get_question);
+
+    let search = warp::get()
+        .and(warp::path("search"))
+        .and(warp::path::end())
+        .and(warp::query())
+        .and(store_filter.clone())
+        .and_then(routes::search::search);
+
     let update_question = warp::put()
         .and(warp::path("questions"))
         .and(warp::path::param::<i32>())
@@ -246,6 +284,25 @@ async fn build_routes(store: store::Store) -> impl Filter<Extract = impl Reply>
         .and(warp::body::json())
         .and_then(routes::question::update_question);
 
+    let patch_question = warp::patch()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(routes::question::merge_patch_body())
+        .and_then(routes::question::patch_question);
+
+    let set_question_status = warp::put()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("status"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::question::set_question_status);
+
     let delete_question = warp::delete()
         .and(warp::path("questions"))
         .and(warp::path::param::<i32>())
@@ -254,46 +311,642 @@ async fn build_routes(store: store::Store) -> impl Filter<Extract = impl Reply>
         .and(store_filter.clone())
         .and_then(routes::question::delete_question);
 
-    let add_question = warp::post()
+    let get_deleted_questions = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path("trash"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::question::get_deleted_questions);
+
+    let restore_question = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("restore"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::question::restore_question);
+
+    let get_revisions = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("revisions"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(routes::revision::get_revisions);
+
+    let get_revision_diff = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("revisions"))
+        .and(warp::path("diff"))
+        .and(warp::path::end())
+        .and(warp::query())
+        .and(store_filter.clone())
+        .and_then(routes::revision::get_revision_diff);
+
+    let rollback_revision = warp::post()
         .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("revisions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("rollback"))
         .and(warp::path::end())
         .and(routes::authentication::auth())
         .and(store_filter.clone())
+        .and_then(routes::revision::rollback_revision);
+
+    let add_question = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
         .and(warp::body::json())
         .and_then(routes::question::add_question);
 
     let add_answer = warp::post()
         .and(warp::path("answers"))
         .and(warp::path::end())
-        .and(routes::authentication::auth())
+        .and(verified.clone())
         .and(store_filter.clone())
         .and(warp::body::form())
         .and_then(routes::answer::add_answer);
 
+    let get_answers = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("answers"))
+        .and(warp::path::end())
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::answer::get_answers);
+
+    let get_answer = warp::get()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::answer::get_answer);
+
+    let update_answer = warp::put()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::answer::update_answer);
+
+    let delete_answer = warp::delete()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(files_filter.clone())
+        .and_then(routes::answer::delete_answer);
+
+    let accept_answer = warp::put()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("accepted-answer"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::answer::accept_answer);
+
+    let unaccept_answer = warp::delete()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("accepted-answer"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::answer::unaccept_answer);
+
+    let vote_question = warp::put()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("vote"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::vote::vote_question);
+
+    let retract_question_vote = warp::delete()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("vote"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::vote::retract_question_vote);
+
+    let vote_answer = warp::put()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("vote"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::vote::vote_answer);
+
+    let retract_answer_vote = warp::delete()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("vote"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::vote::retract_answer_vote);
+
+    let add_question_reaction = warp::put()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("reactions"))
+        .and(warp::path::param::<types::reaction::Reaction>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::reaction::add_question_reaction);
+
+    let remove_question_reaction = warp::delete()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("reactions"))
+        .and(warp::path::param::<types::reaction::Reaction>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::reaction::remove_question_reaction);
+
+    let add_answer_reaction = warp::put()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("reactions"))
+        .and(warp::path::param::<types::reaction::Reaction>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::reaction::add_answer_reaction);
+
+    let remove_answer_reaction = warp::delete()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("reactions"))
+        .and(warp::path::param::<types::reaction::Reaction>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::reaction::remove_answer_reaction);
+
+    let add_question_comment = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("comments"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::comment::add_question_comment);
+
+    let add_answer_comment = warp::post()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("comments"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::comment::add_answer_comment);
+
+    let update_comment = warp::put()
+        .and(warp::path("comments"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::comment::update_comment);
+
+    let delete_comment = warp::delete()
+        .and(warp::path("comments"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::comment::delete_comment);
+
+    let suggest_question_edit = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::suggested_edit::suggest_question_edit);
+
+    let suggest_answer_edit = warp::post()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::suggested_edit::suggest_answer_edit);
+
+    let get_suggested_edits = warp::get()
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::suggested_edit::get_suggested_edits);
+
+    let approve_suggested_edit = warp::post()
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("approve"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::suggested_edit::approve_suggested_edit);
+
+    let improve_suggested_edit = warp::post()
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("improve"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::suggested_edit::improve_suggested_edit);
+
+    let reject_suggested_edit = warp::post()
+        .and(warp::path("suggested-edits"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("reject"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::suggested_edit::reject_suggested_edit);
+
+    let get_question_contributors = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("contributors"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(routes::suggested_edit::get_question_contributors);
+
+    let get_answer_contributors = warp::get()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("contributors"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(routes::suggested_edit::get_answer_contributors);
+
+    let add_bounty = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("bounty"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::bounty::add_bounty);
+
+    let get_bounty = warp::get()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("bounty"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and_then(routes::bounty::get_bounty);
+
+    let award_bounty = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("bounty"))
+        .and(warp::path("award"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::bounty::award_bounty);
+
+    let get_points = warp::get()
+        .and(warp::path("points"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::bounty::get_points);
+
+    let add_draft = warp::post()
+        .and(warp::path("drafts"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::draft::add_draft);
+
+    let get_drafts = warp::get()
+        .and(warp::path("drafts"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::draft::get_drafts);
+
+    let get_draft = warp::get()
+        .and(warp::path("drafts"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::draft::get_draft);
+
+    let update_draft = warp::put()
+        .and(warp::path("drafts"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::draft::update_draft);
+
+    let delete_draft = warp::delete()
+        .and(warp::path("drafts"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::draft::delete_draft);
+
+    let publish_draft = warp::post()
+        .and(warp::path("drafts"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("publish"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and_then(routes::draft::publish_draft);
+
+    let upload_question_attachment = warp::post()
+        .and(warp::path("questions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("attachments"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(files_filter.clone())
+        .and(warp::multipart::form().max_length(max_form_size))
+        .and_then(routes::attachment::upload_question_attachment);
+
+    let upload_answer_attachment = warp::post()
+        .and(warp::path("answers"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("attachments"))
+        .and(warp::path::end())
+        .and(verified.clone())
+        .and(store_filter.clone())
+        .and(files_filter.clone())
+        .and(warp::multipart::form().max_length(max_form_size))
+        .and_then(routes::attachment::upload_answer_attachment);
+
+    let get_attachment = warp::get()
+        .and(warp::path("attachments"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(files_filter.clone())
+        .and_then(routes::attachment::get_attachment);
+
+    let delete_attachment = warp::delete()
+        .and(warp::path("attachments"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(files_filter.clone())
+        .and_then(routes::attachment::delete_attachment);
+
+    let get_user = warp::get()
+        .and(warp::path("users"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::optional_auth())
+        .and(store_filter.clone())
+        .and_then(routes::profile::get_user);
+
+    let update_user = warp::put()
+        .and(warp::path("users"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::profile::update_user);
+
+    let get_me = warp::get()
+        .and(warp::path("me"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::profile::get_me);
+
+    let get_accounts = warp::get()
+        .and(warp::path("admin"))
+        .and(warp::path("accounts"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth_with_role(
+            types::account::Role::Admin,
+        ))
+        .and(store_filter.clone())
+        .and_then(routes::admin::get_accounts);
+
+    let set_account_role = warp::put()
+        .and(warp::path("admin"))
+        .and(warp::path("accounts"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path("role"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth_with_role(
+            types::account::Role::Admin,
+        ))
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::admin::set_account_role);
+
+    let public_keys = warp::get()
+        .and(warp::path(".well-known"))
+        .and(warp::path("paseto-keys"))
+        .and(warp::path::end())
+        .and_then(routes::authentication::get_public_keys);
+
     let registration = warp::post()
         .and(warp::path("registration"))
         .and(warp::path::end())
         .and(store_filter.clone())
+        .and(mailer_filter.clone())
+        .and(public_url_filter.clone())
         .and(warp::body::json())
         .and_then(routes::authentication::register);
 
+    let verify_email = warp::get()
+        .and(warp::path("verification"))
+        .and(warp::path::end())
+        .and(warp::query())
+        .and(store_filter.clone())
+        .and_then(routes::verification::verify_email);
+
+    let resend_verification = warp::post()
+        .and(warp::path("verification"))
+        .and(warp::path("resend"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and(mailer_filter.clone())
+        .and(public_url_filter.clone())
+        .and_then(routes::verification::resend_verification);
+
     let login = warp::post()
         .and(warp::path("login"))
         .and(warp::path::end())
         .and(store_filter.clone())
+        .and(warp::header::optional::<String>("user-agent"))
         .and(warp::body::json())
         .and_then(routes::authentication::login);
 
+    let refresh_token = warp::post()
+        .and(warp::path("token"))
+        .and(warp::path("refresh"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::authentication::refresh_token);
+
+    let logout = warp::post()
+        .and(warp::path("logout"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::authentication::logout);
+
+    let get_sessions = warp::get()
+        .and(warp::path("sessions"))
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::session::get_sessions);
+
+    let delete_session = warp::delete()
+        .and(warp::path("sessions"))
+        .and(warp::path::param::<i32>())
+        .and(warp::path::end())
+        .and(routes::authentication::auth())
+        .and(store_filter.clone())
+        .and_then(routes::session::delete_session);
+
+    let forgot_password = warp::post()
+        .and(warp::path("password"))
+        .and(warp::path("forgot"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(mailer_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::password::forgot_password);
+
+    let reset_password = warp::post()
+        .and(warp::path("password"))
+        .and(warp::path("reset"))
+        .and(warp::path::end())
+        .and(store_filter.clone())
+        .and(warp::body::json())
+        .and_then(routes::password::reset_password);
+
     get_questions
+        .or(get_question)
+        .or(search)
         .or(add_question)
         .or(update_question)
+        .or(patch_question)
+        .or(set_question_status)
         .or(add_answer)
+        .or(get_answers)
+        .or(get_answer)
+        .or(update_answer)
+        .or(delete_answer)
+        .or(accept_answer)
+        .or(unaccept_answer)
+        .or(vote_question)
+        .or(retract_question_vote)
+        .or(vote_answer)
+        .or(retract_answer_vote)
+        .or(add_question_reaction)
+        .or(remove_question_reaction)
+        .or(add_answer_reaction)
+        .or(remove_answer_reaction)
+        .or(add_question_comment)
+        .or(add_answer_comment)
+        .or(update_comment)
+        .or(delete_comment)
+        .or(suggest_question_edit)
+        .or(suggest_answer_edit)
+        .or(get_suggested_edits)
+        .or(approve_suggested_edit)
+        .or(improve_suggested_edit)
+        .or(reject_suggested_edit)
+        .or(get_question_contributors)
+        .or(get_answer_contributors)
+        .or(add_bounty)
+        .or(get_bounty)
+        .or(award_bounty)
+        .or(get_points)
+        .or(add_draft)
+        .or(get_drafts)
+        .or(get_draft)
+        .or(update_draft)
+        .or(delete_draft)
+        .or(publish_draft)
+        .or(upload_question_attachment)
+        .or(upload_answer_attachment)
+        .or(get_attachment)
+        .or(delete_attachment)
+        .or(get_user)
+        .or(update_user)
+        .or(get_me)
+        .or(get_accounts)
+        .or(set_account_role)
         .or(delete_question)
+        .or(get_deleted_questions)
+        .or(restore_question)
+        .or(get_revisions)
+        .or(get_revision_diff)
+        .or(rollback_revision)
         .or(registration)
         .or(login)
+        .or(public_keys)
+        .or(refresh_token)
+        .or(logout)
+        .or(get_sessions)
+        .or(delete_session)
+        .or(verify_email)
+        .or(resend_verification)
+        .or(forgot_password)
+        .or(reset_password)
         .with(cors)
         .with(warp::trace::request())
         .recover(handle_errors::return_error)
+        // Boxed so the nested filter types stay out of everything that serves the routes
+        .map(Reply::into_response)
+        .boxed()
 }
 
 pub async fn setup_store(config: &config::Config) -> Result<store::Store, handle_errors::Error> {
@@ -326,12 +979,40 @@ pub async fn setup_store(config: &config::Config) -> Result<store::Store, handle
 }
 
 pub async fn run(config: config::Config, store: store::Store) {
-    let routes = build_routes(store).await;
+    keyring::init().expect("Invalid PASETO keys");
+    let files = storage::FileStore::from_config(&config).expect("Cannot set up attachment storage");
+
+    tokio::spawn(jobs::purge_trash(
+        store.clone(),
+        files.clone(),
+        config.trash_retention_days,
+    ));
+    tokio::spawn(jobs::expire_bounties(store.clone()));
+    tokio::spawn(jobs::sync_revoked_tokens(store.clone()));
+    tokio::spawn(jobs::purge_sessions(store.clone()));
+
+    let mailer = mailer::from_config(&config).expect("Cannot set up mailer");
+    let routes = build_routes(store, files, mailer, config.public_url.clone()).await;
     warp::serve(routes).run(([0, 0, 0, 0], config.port)).await;
 }
 
 pub async fn oneshot(store: store::Store) -> OneshotHandler {
-    let routes = build_routes(store).await;
+    keyring::init().expect("Invalid PASETO keys");
+    let files = storage::FileStore::new(
+        Arc::new(storage::LocalStorage::new(
+            std::env::temp_dir().join("question_and_answer_attachments"),
+        )),
+        10 * 1024 * 1024,
+        100 * 1024 * 1024,
+    );
+    let mailer = mailer::MemoryMailer::new();
+    let routes = build_routes(
+        store,
+        files,
+        Arc::new(mailer.clone()),
+        "http://localhost:3030".to_string(),
+    )
+    .await;
 
     let (tx, rx) = oneshot::channel::<i32>();
 
@@ -346,7 +1027,7 @@ pub async fn oneshot(store: store::Store) -> OneshotHandler {
 
     tokio::task::spawn(server);
 
-    OneshotHandler { sender: tx }
+    OneshotHandler { sender: tx, mailer }
 }
 // let question = Question::new(
 //     QuestionId::from_str("1").expect("No id provided"),
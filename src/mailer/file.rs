@@ -0,0 +1,67 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use handle_errors::Error;
+
+use super::{Email, Mailer};
+
+/// Writes every email to its own file in a directory instead of sending it
+#[derive(Debug, Clone)]
+pub struct FileMailer {
+    root: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        FileMailer {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, email: Email) -> Result<(), Error> {
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| Error::MailError(e.to_string()))?;
+
+        let path = self.root.join(format!("{}.eml", uuid::Uuid::new_v4()));
+        let content = format!(
+            "To: {}\nSubject: {}\n\n{}\n",
+            email.to, email.subject, email.body
+        );
+
+        tokio::fs::write(path, content)
+            .await
+            .map_err(|e| Error::MailError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod file_mailer_tests {
+    use super::{Email, FileMailer, Mailer};
+
+    #[tokio::test]
+    async fn writes_email() {
+        let root = std::env::temp_dir().join(format!("file_mailer_{}", uuid::Uuid::new_v4()));
+        let mailer = FileMailer::new(&root);
+
+        mailer
+            .send(Email {
+                to: "a@b.c".to_string(),
+                subject: "Hello".to_string(),
+                body: "Some text".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut entries = tokio::fs::read_dir(&root).await.unwrap();
+        let entry = entries.next_entry().await.unwrap().unwrap();
+        let content = tokio::fs::read_to_string(entry.path()).await.unwrap();
+        assert_eq!(content, "To: a@b.c\nSubject: Hello\n\nSome text\n");
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use handle_errors::Error;
+
+use super::{Email, Mailer};
+
+/// Keeps sent emails in memory, clones share the same outbox
+#[derive(Debug, Clone, Default)]
+pub struct MemoryMailer {
+    outbox: Arc<Mutex<Vec<Email>>>,
+}
+
+impl MemoryMailer {
+    pub fn new() -> Self {
+        MemoryMailer::default()
+    }
+
+    /// Every email sent so far, oldest first
+    pub fn sent(&self) -> Vec<Email> {
+        self.outbox.lock().expect("mailer outbox poisoned").clone()
+    }
+}
+
+#[async_trait]
+impl Mailer for MemoryMailer {
+    async fn send(&self, email: Email) -> Result<(), Error> {
+        self.outbox
+            .lock()
+            .expect("mailer outbox poisoned")
+            .push(email);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod memory_mailer_tests {
+    use super::{Email, Mailer, MemoryMailer};
+
+    #[tokio::test]
+    async fn clones_share_outbox() {
+        let mailer = MemoryMailer::new();
+        let email = Email {
+            to: "a@b.c".to_string(),
+            subject: "Hello".to_string(),
+            body: "Some text".to_string(),
+        };
+
+        mailer.clone().send(email.clone()).await.unwrap();
+
+        assert_eq!(mailer.sent(), vec![email]);
+    }
+}
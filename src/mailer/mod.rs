@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use handle_errors::Error;
+
+use crate::config::Config;
+
+mod file;
+mod memory;
+mod smtp;
+
+pub use self::file::FileMailer;
+pub use self::memory::MemoryMailer;
+pub use self::smtp::SmtpMailer;
+
+/// A plain text email to a single recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// Delivers the emails the service sends, like password reset tokens
+#[async_trait]
+pub trait Mailer: std::fmt::Debug + Send + Sync {
+    async fn send(&self, email: Email) -> Result<(), Error>;
+}
+
+/// Picks the mailer set by `mailer`, either `smtp` or `file`
+pub fn from_config(config: &Config) -> Result<Arc<dyn Mailer>, Error> {
+    match config.mailer.as_str() {
+        "smtp" => Ok(Arc::new(SmtpMailer::new(
+            &config.smtp_host,
+            config.smtp_port,
+            &config.smtp_username,
+            &config.smtp_password,
+            &config.mail_from,
+        )?)),
+        "file" => Ok(Arc::new(FileMailer::new(&config.mail_path))),
+        other => Err(Error::InvalidParameter(format!(
+            "mailer must be 'smtp' or 'file', got '{}'",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,73 @@
+use async_trait::async_trait;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use handle_errors::Error;
+
+use super::{Email, Mailer};
+
+/// Sends emails through an SMTP server, e.g. a local MailHog or the relay of a mail provider
+#[derive(Debug, Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    /// Connects over TLS when `username` is set, implicit TLS on port 465 and STARTTLS on any
+    /// other port. Without credentials it connects in plaintext, which is only meant for
+    /// servers like a local MailHog.
+    pub fn new(
+        host: &str,
+        port: u16,
+        username: &str,
+        password: &str,
+        from: &str,
+    ) -> Result<Self, Error> {
+        let from = from
+            .parse::<Mailbox>()
+            .map_err(|e| Error::InvalidParameter(format!("mail from address: {}", e)))?;
+
+        let builder = if username.is_empty() {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+        } else {
+            let relay = if port == 465 {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            } else {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            };
+            relay
+                .map_err(|e| Error::MailError(e.to_string()))?
+                .credentials(Credentials::new(username.to_string(), password.to_string()))
+        };
+
+        Ok(SmtpMailer {
+            transport: builder.port(port).build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, email: Email) -> Result<(), Error> {
+        let to = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| Error::MailError(e.to_string()))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(email.body)
+            .map_err(|e| Error::MailError(e.to_string()))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| Error::MailError(e.to_string()))
+    }
+}
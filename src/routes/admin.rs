@@ -0,0 +1,24 @@
+use crate::store::Store;
+use crate::types::account::{RoleUpdate, Session};
+
+pub async fn get_accounts(
+    _session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_accounts().await {
+        Ok(accounts) => Ok(warp::reply::json(&accounts)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn set_account_role(
+    id: i32,
+    _session: Session,
+    store: Store,
+    update: RoleUpdate,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.set_account_role(id, update.role).await {
+        Ok(account) => Ok(warp::reply::json(&account)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
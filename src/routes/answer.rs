@@ -2,9 +2,12 @@
 use warp::http::StatusCode;
 
 use crate::profanity::check_profanity;
+use crate::routes::attachment::fill_attachments;
+use crate::routes::reaction::fill_reactions;
+use crate::storage::FileStore;
 use crate::store::Store;
 use crate::types::account::Session;
-use crate::types::answer::NewAnswer;
+use crate::types::answer::{AcceptedAnswer, AnswerUpdate, NewAnswer};
 
 pub async fn add_answer(
     session: Session,
@@ -13,6 +16,11 @@ pub async fn add_answer(
     // params: HashMap<String, String>,
 ) -> Result<impl warp::Reply, warp::Rejection> {
     let account_id = session.account_id;
+
+    if !store.is_question_open(new_answer.question_id.0).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
     let content = match check_profanity(new_answer.content).await {
         Ok(res) => res,
         Err(e) => return Err(warp::reject::custom(e)),
@@ -23,7 +31,13 @@ pub async fn add_answer(
     };
 
     match store.add_answer(answer, account_id).await {
-        Ok(_) => Ok(warp::reply::with_status("Answer added", StatusCode::OK)),
+        Ok(answer) => {
+            let location = format!("/answers/{}", answer.id.0);
+            Ok(warp::reply::with_status(
+                warp::reply::with_header(warp::reply::json(&answer), "Location", location),
+                StatusCode::CREATED,
+            ))
+        }
         Err(e) => Err(warp::reject::custom(e)),
     }
 
@@ -41,3 +55,138 @@ pub async fn add_answer(
 
     // Ok(warp::reply::with_status("Answer added", StatusCode::OK))
 }
+
+pub async fn get_answers(
+    question_id: i32,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_question(question_id).await?;
+
+    match store.clone().get_answers(question_id).await {
+        Ok(mut res) => {
+            let account_id = session.map(|session| session.account_id);
+            fill_reactions(&store, &mut [], &mut res, account_id.as_ref()).await?;
+            fill_attachments(&store, &mut [], &mut res).await?;
+            Ok(warp::reply::json(&res))
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_answer(
+    id: i32,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.clone().get_answer(id).await {
+        Ok(mut res) => {
+            let account_id = session.map(|session| session.account_id);
+            fill_reactions(
+                &store,
+                &mut [],
+                std::slice::from_mut(&mut res),
+                account_id.as_ref(),
+            )
+            .await?;
+            fill_attachments(&store, &mut [], std::slice::from_mut(&mut res)).await?;
+            Ok(warp::reply::json(&res))
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn update_answer(
+    id: i32,
+    session: Session,
+    store: Store,
+    answer: AnswerUpdate,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !session.is_moderator() && !store.is_answer_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    let current = store.clone().get_answer(id).await?;
+    if !store.is_question_open(current.question_id.0).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
+    let content = check_profanity(answer.content).await?;
+
+    match store.update_answer(id, content).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn delete_answer(
+    id: i32,
+    session: Session,
+    store: Store,
+    files: FileStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !session.is_moderator() && !store.is_answer_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    match store.delete_answer(id).await {
+        Ok(storage_keys) => {
+            files.delete_files(&storage_keys).await;
+            Ok(warp::reply::with_status(
+                format!("Answer {} deleted", id),
+                StatusCode::OK,
+            ))
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn accept_answer(
+    question_id: i32,
+    session: Session,
+    store: Store,
+    accepted: AcceptedAnswer,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !store
+        .is_question_owner(question_id, &session.account_id)
+        .await?
+    {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    let answer = store.clone().get_answer(accepted.answer_id.0).await?;
+    if answer.question_id.0 != question_id {
+        return Err(warp::reject::custom(
+            handle_errors::Error::InvalidParameter(format!(
+                "answer {} does not belong to question {}",
+                answer.id.0, question_id
+            )),
+        ));
+    }
+
+    match store
+        .set_accepted_answer(question_id, Some(answer.id))
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn unaccept_answer(
+    question_id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !store
+        .is_question_owner(question_id, &session.account_id)
+        .await?
+    {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    match store.set_accepted_answer(question_id, None).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
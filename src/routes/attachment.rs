@@ -0,0 +1,222 @@
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::TryStreamExt;
+use warp::http::{
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+    Response, StatusCode,
+};
+use warp::multipart::FormData;
+
+use crate::storage::FileStore;
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::answer::{Answer, AnswerId};
+use crate::types::attachment::{sniff_content_type, NewAttachment};
+use crate::types::post::Post;
+use crate::types::question::{Question, QuestionId};
+
+pub async fn upload_question_attachment(
+    id: i32,
+    session: Session,
+    store: Store,
+    files: FileStore,
+    form: FormData,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !store.is_question_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    if !store.is_question_open(id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
+    upload(Post::Question(QuestionId(id)), session, store, files, form).await
+}
+
+pub async fn upload_answer_attachment(
+    id: i32,
+    session: Session,
+    store: Store,
+    files: FileStore,
+    form: FormData,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !store.is_answer_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    let answer = store.clone().get_answer(id).await?;
+    if !store.is_question_open(answer.question_id.0).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
+    upload(Post::Answer(AnswerId(id)), session, store, files, form).await
+}
+
+/// Stores the `file` field of the form and attaches it to the post
+async fn upload(
+    post: Post,
+    session: Session,
+    store: Store,
+    files: FileStore,
+    form: FormData,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+
+    let (file_name, data) = read_file(form, files.max_file_size).await?;
+    let content_type =
+        sniff_content_type(&data).ok_or(handle_errors::Error::UnsupportedMediaType)?;
+
+    // Saves storing files that cannot fit, the quota is enforced when adding the attachment
+    let usage = store.clone().get_attachment_usage(&account_id).await?;
+    if usage + data.len() as i64 > files.account_quota {
+        return Err(warp::reject::custom(
+            handle_errors::Error::AttachmentQuotaExceeded,
+        ));
+    }
+
+    let attachment = NewAttachment {
+        file_name,
+        content_type: content_type.to_string(),
+        size: data.len() as i64,
+        storage_key: format!("attachments/{}", uuid::Uuid::new_v4()),
+    };
+    files
+        .backend
+        .put(&attachment.storage_key, content_type, data)
+        .await?;
+
+    let storage_key = attachment.storage_key.clone();
+    match store
+        .add_attachment(&post, &account_id, attachment, files.account_quota)
+        .await
+    {
+        Ok(attachment) => Ok(warp::reply::with_status(
+            warp::reply::with_header(
+                warp::reply::json(&attachment),
+                "Location",
+                attachment.url.clone(),
+            ),
+            StatusCode::CREATED,
+        )),
+        Err(e) => {
+            // Don't keep files nothing refers to
+            if let Err(e) = files.backend.delete(&storage_key).await {
+                tracing::event!(tracing::Level::ERROR, "{}", e);
+            }
+            Err(warp::reject::custom(e))
+        }
+    }
+}
+
+/// Reads the `file` field of a multipart form, failing as soon as it gets bigger than `max_size`
+async fn read_file(form: FormData, max_size: u64) -> Result<(String, Bytes), handle_errors::Error> {
+    let read_error = |e: warp::Error| {
+        handle_errors::Error::InvalidParameter(format!("cannot read upload: {}", e))
+    };
+
+    let mut parts = Box::pin(form);
+    while let Some(part) = parts.try_next().await.map_err(read_error)? {
+        if part.name() != "file" {
+            continue;
+        }
+
+        // Only the name itself, never a path the client sent along
+        let file_name = part
+            .filename()
+            .and_then(|name| name.rsplit(['/', '\\']).next())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("attachment")
+            .chars()
+            .take(255)
+            .collect::<String>();
+
+        let mut data = BytesMut::new();
+        let mut chunks = Box::pin(part.stream());
+        while let Some(chunk) = chunks.try_next().await.map_err(read_error)? {
+            data.put(chunk);
+            if data.len() as u64 > max_size {
+                return Err(handle_errors::Error::AttachmentTooLarge);
+            }
+        }
+
+        return Ok((file_name, data.freeze()));
+    }
+
+    Err(handle_errors::Error::InvalidParameter(String::from(
+        "form has no 'file' field",
+    )))
+}
+
+pub async fn get_attachment(
+    id: i32,
+    store: Store,
+    files: FileStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let attachment = store.get_attachment(id).await?;
+    let data = files.backend.get(&attachment.storage_key).await?;
+
+    // Images are shown in the browser, everything else is downloaded
+    let disposition = if attachment.content_type.starts_with("image/") {
+        "inline"
+    } else {
+        "attachment"
+    };
+    let file_name = attachment
+        .file_name
+        .replace(|c: char| c == '"' || c == '\\' || c.is_control(), "_");
+
+    Response::builder()
+        .header(CONTENT_TYPE, attachment.content_type)
+        .header(
+            CONTENT_DISPOSITION,
+            format!("{}; filename=\"{}\"", disposition, file_name),
+        )
+        .header(X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .body(warp::hyper::Body::from(data))
+        .map_err(|e| warp::reject::custom(handle_errors::Error::StorageError(e.to_string())))
+}
+
+pub async fn delete_attachment(
+    id: i32,
+    session: Session,
+    store: Store,
+    files: FileStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let attachment = store.clone().get_attachment(id).await?;
+    if attachment.account_id != session.account_id {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    store.delete_attachment(id).await?;
+    files.delete_files(&[attachment.storage_key]).await;
+
+    Ok(warp::reply::with_status(
+        format!("Attachment {} deleted", id),
+        StatusCode::OK,
+    ))
+}
+
+/// Sets `attachments` on all given questions and answers with a single query
+pub async fn fill_attachments(
+    store: &Store,
+    questions: &mut [Question],
+    answers: &mut [Answer],
+) -> Result<(), handle_errors::Error> {
+    let posts = questions
+        .iter()
+        .map(|question| Post::Question(question.id.clone()))
+        .chain(answers.iter().map(|answer| Post::Answer(answer.id.clone())))
+        .collect::<Vec<Post>>();
+    if posts.is_empty() {
+        return Ok(());
+    }
+
+    let mut attachments = store.clone().get_attachments(&posts).await?;
+    for question in questions.iter_mut() {
+        let post = Post::Question(question.id.clone());
+        question.attachments = attachments.remove(&post).unwrap_or_default();
+    }
+    for answer in answers.iter_mut() {
+        let post = Post::Answer(answer.id.clone());
+        answer.attachments = attachments.remove(&post).unwrap_or_default();
+    }
+
+    Ok(())
+}
@@ -2,23 +2,68 @@ use argon2::{self, Config};
 use chrono::prelude::*;
 // use paseto::v2::local_paseto;
 use rand::Rng;
-use std::{env, future};
-use warp::{http::StatusCode, Filter};
+use std::collections::HashSet;
+use std::future;
+use std::sync::{Arc, LazyLock, OnceLock, RwLock};
+use warp::Filter;
 
+use crate::keyring::{keyring, Keyring};
+use crate::mailer::Mailer;
+use crate::routes::verification::send_verification_email;
 use crate::store::Store;
-use crate::types::account::{Account, AccountId, Session};
+use crate::types::account::{
+    Account, AccountId, EmailVerification, Role, Session, VERIFICATION_TOKEN_HOURS,
+};
+use crate::types::session::{RefreshRequest, TokenPair, ACCESS_TOKEN_MINUTES, REFRESH_TOKEN_DAYS};
+use crate::types::token::{generate_token, hash_token};
+
+/// jti of revoked access tokens that have not expired yet.
+/// Updated on every revocation here and reloaded from the database by `jobs::sync_revoked_tokens`,
+/// which also picks up revocations made by other instances.
+static REVOKED_TOKENS: LazyLock<RwLock<HashSet<String>>> = LazyLock::new(Default::default);
+
+pub fn revoke_token(jti: String) {
+    REVOKED_TOKENS
+        .write()
+        .expect("revocation list poisoned")
+        .insert(jti);
+}
+
+pub fn set_revoked_tokens(jtis: HashSet<String>) {
+    *REVOKED_TOKENS.write().expect("revocation list poisoned") = jtis;
+}
+
+fn is_revoked(jti: &str) -> bool {
+    REVOKED_TOKENS
+        .read()
+        .expect("revocation list poisoned")
+        .contains(jti)
+}
+
+/// Adds an unverified account and emails it a verification link
+pub async fn register(
+    store: Store,
+    mailer: Arc<dyn Mailer>,
+    public_url: String,
+    account: Account,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    account.validate_email()?;
 
-pub async fn register(store: Store, account: Account) -> Result<impl warp::Reply, warp::Rejection> {
     let hashed_password = hash_password(account.password.as_bytes());
 
     let account = Account {
         id: account.id,
         email: account.email,
         password: hashed_password,
+        role: Role::User,
     };
+    let email = account.email.clone();
 
     match store.add_account(account).await {
-        Ok(_) => Ok(warp::reply::json(&"Account added".to_string())),
+        Ok(account_id) => {
+            send_verification_email(mailer, &public_url, &account_id, email);
+            Ok(warp::reply::json(&"Account added".to_string()))
+        }
         Err(e) => Err(warp::reject::custom(e)),
     }
 }
@@ -29,14 +74,36 @@ pub fn hash_password(password: &[u8]) -> String {
     argon2::hash_encoded(password, &salt, &config).unwrap()
 }
 
-pub async fn login(store: Store, login: Account) -> Result<impl warp::Reply, warp::Rejection> {
-    match store.get_account(login.email).await {
+/// Starts a new session, `user_agent` is only kept to tell the sessions apart
+pub async fn login(
+    store: Store,
+    user_agent: Option<String>,
+    login: Account,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.clone().get_account(login.email).await {
         Ok(account) => match verify_password(&account.password, login.password.as_bytes()) {
             Ok(verified) => {
                 if verified {
-                    Ok(warp::reply::json(&issue_token(
-                        account.id.expect("id not found"),
-                    )))
+                    let account_id = account.id.expect("id not found");
+                    let refresh_token = generate_token();
+                    let jti = uuid::Uuid::new_v4().to_string();
+                    let user_agent = user_agent.map(|agent| agent.chars().take(255).collect());
+
+                    store
+                        .add_session(
+                            &account_id,
+                            &hash_token(&refresh_token),
+                            &jti,
+                            user_agent,
+                            REFRESH_TOKEN_DAYS,
+                        )
+                        .await?;
+
+                    Ok(warp::reply::json(&TokenPair {
+                        access_token: issue_token(account_id, account.role, &jti),
+                        refresh_token,
+                        expires_in: i64::from(ACCESS_TOKEN_MINUTES) * 60,
+                    }))
                 } else {
                     Err(warp::reject::custom(handle_errors::Error::WrongPassword))
                 }
@@ -49,42 +116,116 @@ pub async fn login(store: Store, login: Account) -> Result<impl warp::Reply, war
     }
 }
 
+/// Trades a refresh token for a new access and refresh token, the old ones stop working
+pub async fn refresh_token(
+    store: Store,
+    request: RefreshRequest,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let refresh_token = generate_token();
+    let jti = uuid::Uuid::new_v4().to_string();
+
+    let (account_id, role, revoked) = store
+        .refresh_session(
+            &hash_token(&request.refresh_token),
+            &hash_token(&refresh_token),
+            &jti,
+            ACCESS_TOKEN_MINUTES,
+        )
+        .await?;
+    revoke_token(revoked);
+
+    Ok(warp::reply::json(&TokenPair {
+        access_token: issue_token(account_id, role, &jti),
+        refresh_token,
+        expires_in: i64::from(ACCESS_TOKEN_MINUTES) * 60,
+    }))
+}
+
+/// Ends the session the access token belongs to
+pub async fn logout(session: Session, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+    let revoked = store
+        .revoke_session(
+            &session.account_id,
+            None,
+            Some(&session.jti),
+            ACCESS_TOKEN_MINUTES,
+        )
+        .await?;
+    revoke_token(revoked);
+
+    Ok(warp::reply::json(&"Logged out"))
+}
+
 fn verify_password(hash: &str, password: &[u8]) -> Result<bool, argon2::Error> {
     argon2::verify_encoded(hash, password)
 }
 
+/// Accepts tokens of any key in the keyring, see `keyring::Keyring`
 pub fn verify_token(token: String) -> Result<Session, handle_errors::Error> {
-    let key = env::var("PASETO_KEY").unwrap();
-    let token = paseto::tokens::validate_local_token(
-        &token,
-        None,
-        key.as_bytes(),
-        &paseto::tokens::TimeBackend::Chrono,
-    )
-    .map_err(|_| handle_errors::Error::CannotDecryptToken)?;
+    let token = keyring().verify(&token)?;
 
-    serde_json::from_value::<Session>(token).map_err(|_| handle_errors::Error::CannotDecryptToken)
+    let session = serde_json::from_value::<Session>(token)
+        .map_err(|_| handle_errors::Error::CannotDecryptToken)?;
+    if is_revoked(&session.jti) {
+        return Err(handle_errors::Error::Unauthorized);
+    }
+    Ok(session)
 }
 
-fn issue_token(account_id: AccountId) -> String {
-    let key = env::var("PASETO_KEY").unwrap();
-
+/// Issued with the current key, as `v2.public` token when public keys are configured
+fn issue_token(account_id: AccountId, role: Role, jti: &str) -> String {
     let current_date_time = Utc::now();
-    let dt = current_date_time + chrono::Duration::days(1);
+    let dt = current_date_time + chrono::Duration::minutes(i64::from(ACCESS_TOKEN_MINUTES));
 
-    paseto::tokens::PasetoBuilder::new()
-        .set_encryption_key(&Vec::from(key.as_bytes()))
-        .set_expiration(&dt)
-        .set_not_before(&Utc::now())
-        .set_claim("account_id", serde_json::json!(account_id))
-        .build()
-        .expect("Failed to construct paseto token w/ builder.")
+    keyring().issue(
+        &[
+            ("account_id", serde_json::json!(account_id)),
+            ("role", serde_json::json!(role)),
+            ("jti", serde_json::json!(jti)),
+        ],
+        &dt,
+    )
 
     // let state = serde_json::to_string(&account_id).expect("Failed to serialize state.");
     // local_paseto(&state, None, "Random words winter macintosh pc".as_bytes())
     //     .expect("Failed to create token.")
 }
 
+/// Verification tokens are encrypted with keys derived from the session keys,
+/// so they can never pass as a session token or the other way round
+fn verification_keyring() -> &'static Keyring {
+    static VERIFICATION_KEYRING: OnceLock<Keyring> = OnceLock::new();
+    VERIFICATION_KEYRING.get_or_init(|| keyring().derive("email_verification"))
+}
+
+pub fn issue_verification_token(account_id: &AccountId, email: &str) -> String {
+    let dt = Utc::now() + chrono::Duration::hours(VERIFICATION_TOKEN_HOURS);
+
+    verification_keyring().issue(
+        &[
+            ("account_id", serde_json::json!(account_id)),
+            ("email", serde_json::json!(email)),
+        ],
+        &dt,
+    )
+}
+
+pub fn verify_verification_token(token: &str) -> Result<EmailVerification, handle_errors::Error> {
+    let token = verification_keyring()
+        .verify(token)
+        .map_err(|_| handle_errors::Error::InvalidVerificationToken)?;
+
+    serde_json::from_value::<EmailVerification>(token)
+        .map_err(|_| handle_errors::Error::InvalidVerificationToken)
+}
+
+/// Public keys of the keyring, so other services can verify tokens without the secret keys
+pub async fn get_public_keys() -> Result<impl warp::Reply, warp::Rejection> {
+    Ok(warp::reply::json(&serde_json::json!({
+        "keys": keyring().public_keys()
+    })))
+}
+
 pub fn auth() -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Clone {
     warp::header::<String>("Authorization").and_then(|token: String| {
         let token = match verify_token(token) {
@@ -99,14 +240,62 @@ pub fn auth() -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Cl
     })
 }
 
+/// Like `auth`, but rejects sessions whose role is below `role`
+///
+/// The role is read from the token, so a changed role only applies after the next refresh
+pub fn auth_with_role(
+    role: Role,
+) -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Clone {
+    auth().and_then(move |session: Session| {
+        if session.role < role {
+            return future::ready(Err(warp::reject::custom(
+                handle_errors::Error::Unauthorized,
+            )));
+        }
+        future::ready(Ok(session))
+    })
+}
+
+/// Like `auth`, but rejects accounts that have not verified their email yet
+///
+/// Checked against the database, so posting works right after following the link
+pub fn auth_verified(
+    store: Store,
+) -> impl Filter<Extract = (Session,), Error = warp::Rejection> + Clone {
+    auth().and_then(move |session: Session| {
+        let store = store.clone();
+        async move {
+            match store.is_email_verified(&session.account_id).await {
+                Ok(true) => Ok(session),
+                Ok(false) => Err(warp::reject::custom(handle_errors::Error::EmailNotVerified)),
+                Err(e) => Err(warp::reject::custom(e)),
+            }
+        }
+    })
+}
+
+/// Like `auth`, but lets requests through as `None` when the `Authorization` header is missing
+/// or its token is invalid, e.g. expired, so public routes stay readable with a stale token
+pub fn optional_auth() -> impl Filter<Extract = (Option<Session>,), Error = warp::Rejection> + Clone
+{
+    warp::header::optional::<String>("Authorization")
+        .map(|token: Option<String>| token.and_then(|token| verify_token(token).ok()))
+}
+
 #[cfg(test)]
 mod authentication_tests {
-    use super::{auth, env, issue_token, AccountId};
+    use super::{
+        auth, auth_with_role, issue_token, issue_verification_token, optional_auth, revoke_token,
+        verify_verification_token, AccountId, Role,
+    };
+    use crate::keyring::keyring;
+    use chrono::prelude::*;
+    use std::env;
 
     #[tokio::test]
     async fn post_questions_auth() {
         env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
-        let token = issue_token(AccountId(3));
+        let token = issue_token(AccountId(3), Role::User, "a");
 
         let filter = auth();
 
@@ -116,4 +305,88 @@ mod authentication_tests {
 
         assert_eq!(res.await.unwrap().account_id, AccountId(3));
     }
+
+    #[tokio::test]
+    async fn revoked_token() {
+        env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
+        let token = issue_token(AccountId(3), Role::User, "revoked");
+        let filter = auth();
+
+        let res = warp::test::request()
+            .header("Authorization", &token)
+            .filter(&filter);
+        assert!(res.await.is_ok());
+
+        revoke_token("revoked".to_string());
+
+        let res = warp::test::request()
+            .header("Authorization", &token)
+            .filter(&filter);
+        assert!(res.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn auth_with_role_rejects_lower_roles() {
+        env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
+        let filter = auth_with_role(Role::Moderator);
+
+        let user = warp::test::request()
+            .header("Authorization", issue_token(AccountId(3), Role::User, "a"))
+            .filter(&filter);
+        assert!(user.await.is_err());
+
+        let admin = warp::test::request()
+            .header("Authorization", issue_token(AccountId(1), Role::Admin, "b"))
+            .filter(&filter);
+        assert_eq!(admin.await.unwrap().role, Role::Admin);
+    }
+
+    #[tokio::test]
+    async fn verification_token_is_no_session() {
+        env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
+        let token = issue_verification_token(&AccountId(3), "a@b.c");
+
+        let verification = verify_verification_token(&token).unwrap();
+        assert_eq!(verification.account_id, AccountId(3));
+        assert_eq!(verification.email, "a@b.c");
+
+        let filter = auth();
+        let res = warp::test::request()
+            .header("Authorization", token)
+            .filter(&filter);
+        assert!(res.await.is_err());
+
+        let session = issue_token(AccountId(3), Role::User, "a");
+        assert!(verify_verification_token(&session).is_err());
+    }
+
+    #[tokio::test]
+    async fn optional_auth_without_token() {
+        let filter = optional_auth();
+
+        let res = warp::test::request().filter(&filter);
+
+        assert_eq!(res.await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn optional_auth_with_invalid_token() {
+        env::set_var("PASETO_KEY", "RANDOM WORDS WINTER MACINTOSH PC");
+        let expired = keyring().issue(
+            &[
+                ("account_id", serde_json::json!(AccountId(3))),
+                ("role", serde_json::json!(Role::User)),
+                ("jti", serde_json::json!("expired")),
+            ],
+            &(Utc::now() - chrono::Duration::minutes(1)),
+        );
+        let filter = optional_auth();
+
+        for token in [expired.as_str(), "garbage"] {
+            let res = warp::test::request()
+                .header("Authorization", token)
+                .filter(&filter);
+            assert_eq!(res.await.unwrap(), None);
+        }
+    }
 }
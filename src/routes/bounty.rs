@@ -0,0 +1,99 @@
+use warp::http::StatusCode;
+
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::bounty::{BountyAward, NewBounty};
+
+pub async fn add_bounty(
+    question_id: i32,
+    session: Session,
+    store: Store,
+    new_bounty: NewBounty,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+    new_bounty.validate()?;
+
+    if !store.is_question_owner(question_id, &account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    if !store.is_question_open(question_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+    match store.clone().get_open_bounty(question_id).await {
+        Ok(_) => {
+            return Err(warp::reject::custom(
+                handle_errors::Error::BountyAlreadyOpen,
+            ))
+        }
+        Err(handle_errors::Error::BountyNotFound) => (),
+        Err(e) => return Err(warp::reject::custom(e)),
+    }
+
+    match store.add_bounty(question_id, &account_id, new_bounty).await {
+        Ok(bounty) => Ok(warp::reply::with_status(
+            warp::reply::json(&bounty),
+            StatusCode::CREATED,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_bounty(
+    question_id: i32,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_question(question_id).await?;
+
+    match store.get_open_bounty(question_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Lets the owner of the question award the bounty before it expires
+pub async fn award_bounty(
+    question_id: i32,
+    session: Session,
+    store: Store,
+    award: BountyAward,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+
+    if !store.is_question_owner(question_id, &account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    let bounty = store.clone().get_open_bounty(question_id).await?;
+
+    let answer = store.clone().get_answer(award.answer_id.0).await?;
+    if answer.question_id.0 != question_id {
+        return Err(warp::reject::custom(
+            handle_errors::Error::InvalidParameter(format!(
+                "answer {} does not belong to question {}",
+                answer.id.0, question_id
+            )),
+        ));
+    }
+    if store.is_answer_owner(answer.id.0, &account_id).await? {
+        return Err(warp::reject::custom(
+            handle_errors::Error::InvalidParameter(String::from(
+                "cannot award a bounty to your own answer",
+            )),
+        ));
+    }
+
+    match store.award_bounty(bounty.id.0, &answer.id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Points balance of the logged in account with the ledger entries behind it
+pub async fn get_points(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_points(&session.account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
@@ -0,0 +1,84 @@
+use warp::http::StatusCode;
+
+use crate::profanity::check_profanity;
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::answer::AnswerId;
+use crate::types::comment::NewComment;
+use crate::types::post::Post;
+use crate::types::question::QuestionId;
+
+pub async fn add_question_comment(
+    id: i32,
+    session: Session,
+    store: Store,
+    new_comment: NewComment,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_question(id).await?;
+
+    add_comment(Post::Question(QuestionId(id)), session, store, new_comment).await
+}
+
+pub async fn add_answer_comment(
+    id: i32,
+    session: Session,
+    store: Store,
+    new_comment: NewComment,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_answer(id).await?;
+
+    add_comment(Post::Answer(AnswerId(id)), session, store, new_comment).await
+}
+
+async fn add_comment(
+    post: Post,
+    session: Session,
+    store: Store,
+    new_comment: NewComment,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let content = check_profanity(new_comment.content).await?;
+
+    match store.add_comment(&post, content, session.account_id).await {
+        Ok(comment) => Ok(warp::reply::with_status(
+            warp::reply::json(&comment),
+            StatusCode::CREATED,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn update_comment(
+    id: i32,
+    session: Session,
+    store: Store,
+    comment: NewComment,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !session.is_moderator() && !store.is_comment_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    let content = check_profanity(comment.content).await?;
+
+    match store.update_comment(id, content).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn delete_comment(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !session.is_moderator() && !store.is_comment_owner(id, &session.account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    match store.delete_comment(id).await {
+        Ok(_) => Ok(warp::reply::with_status(
+            format!("Comment {} deleted", id),
+            StatusCode::OK,
+        )),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
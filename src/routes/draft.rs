@@ -0,0 +1,99 @@
+use warp::http::StatusCode;
+
+use crate::profanity::check_profanity;
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::draft::DraftContent;
+use crate::types::question::NewQuestion;
+
+pub async fn add_draft(
+    session: Session,
+    store: Store,
+    draft: DraftContent,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.add_draft(draft, &session.account_id).await {
+        Ok(draft) => {
+            let location = format!("/drafts/{}", draft.id.0);
+            Ok(warp::reply::with_status(
+                warp::reply::with_header(warp::reply::json(&draft), "Location", location),
+                StatusCode::CREATED,
+            ))
+        }
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_drafts(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_drafts(&session.account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_draft(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_draft(id, &session.account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Autosave, overwrites the draft without checking it for profanity yet
+pub async fn update_draft(
+    id: i32,
+    session: Session,
+    store: Store,
+    draft: DraftContent,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.update_draft(id, draft, &session.account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn delete_draft(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.delete_draft(id, &session.account_id).await {
+        Ok(true) => Ok(warp::reply::with_status(
+            format!("Draft {} deleted", id),
+            StatusCode::OK,
+        )),
+        Ok(false) => Err(warp::reject::custom(handle_errors::Error::DraftNotFound)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Publishes the draft like a new question, deleting it in the same transaction. The draft stays
+/// locked while its title and content are checked, and is kept when publishing fails.
+pub async fn publish_draft(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let question = store
+        .publish_draft(id, &session.account_id, |draft| async move {
+            let question = NewQuestion::try_from(draft)?;
+            let (title, content) = tokio::join!(
+                check_profanity(question.title),
+                check_profanity(question.content)
+            );
+
+            Ok(NewQuestion {
+                title: title?,
+                content: content?,
+                tags: question.tags,
+            })
+        })
+        .await?;
+
+    Ok(warp::reply::json(&question))
+}
@@ -1,3 +1,17 @@
+pub mod admin;
 pub mod answer;
+pub mod attachment;
 pub mod authentication;
+pub mod bounty;
+pub mod comment;
+pub mod draft;
+pub mod password;
+pub mod profile;
 pub mod question;
+pub mod reaction;
+pub mod revision;
+pub mod search;
+pub mod session;
+pub mod suggested_edit;
+pub mod verification;
+pub mod vote;
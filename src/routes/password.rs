@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use warp::http::StatusCode;
+
+use crate::mailer::{Email, Mailer};
+use crate::routes::authentication::{hash_password, revoke_token};
+use crate::store::Store;
+use crate::types::password_reset::{PasswordForgot, PasswordReset, RESET_TOKEN_MINUTES};
+use crate::types::session::ACCESS_TOKEN_MINUTES;
+use crate::types::token::{generate_token, hash_token};
+
+/// Emails a reset token if an account with this email exists.
+/// The response is the same either way, so it cannot be used to look up accounts.
+pub async fn forgot_password(
+    store: Store,
+    mailer: Arc<dyn Mailer>,
+    request: PasswordForgot,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let Some(account_id) = store.clone().find_account_id(&request.email).await? {
+        let token = generate_token();
+        store
+            .add_password_reset(&account_id, &hash_token(&token), RESET_TOKEN_MINUTES)
+            .await?;
+
+        let email = Email {
+            to: request.email,
+            subject: String::from("Reset your password"),
+            body: format!(
+                "Use this token to reset your password: {}\n\nIt expires in {} minutes and can only be used once. If you did not ask for a reset, ignore this email.",
+                token, RESET_TOKEN_MINUTES
+            ),
+        };
+        // Sent in the background so a slow mail server does not give away that the account exists
+        tokio::spawn(async move {
+            if let Err(e) = mailer.send(email).await {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+            }
+        });
+    }
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&"If the account exists, a reset token has been sent"),
+        StatusCode::ACCEPTED,
+    ))
+}
+
+pub async fn reset_password(
+    store: Store,
+    reset: PasswordReset,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reset.validate()?;
+
+    let password = hash_password(reset.password.as_bytes());
+    let revoked = store
+        .reset_password(&hash_token(&reset.token), password, ACCESS_TOKEN_MINUTES)
+        .await?;
+    revoked.into_iter().for_each(revoke_token);
+
+    Ok(warp::reply::json(&"Password updated"))
+}
@@ -0,0 +1,72 @@
+use crate::profanity::check_profanity;
+use crate::routes::attachment::fill_attachments;
+use crate::routes::reaction::fill_reactions;
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::profile::{ProfileUpdate, ProfileWithActivity};
+
+/// How many of the latest questions and answers a profile page lists
+const PROFILE_ACTIVITY_LIMIT: i32 = 20;
+
+pub async fn get_user(
+    id: i32,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let profile = store.clone().get_profile(id).await?;
+    let mut questions = store
+        .clone()
+        .get_account_questions(id, PROFILE_ACTIVITY_LIMIT)
+        .await?;
+    let mut answers = store
+        .clone()
+        .get_account_answers(id, PROFILE_ACTIVITY_LIMIT)
+        .await?;
+
+    let account_id = session.map(|session| session.account_id);
+    fill_reactions(&store, &mut questions, &mut answers, account_id.as_ref()).await?;
+    fill_attachments(&store, &mut questions, &mut answers).await?;
+
+    Ok(warp::reply::json(&ProfileWithActivity {
+        profile,
+        questions,
+        answers,
+    }))
+}
+
+/// The profile page of the logged in account
+pub async fn get_me(session: Session, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+    let id = session.account_id.0;
+    get_user(id, Some(session), store).await
+}
+
+pub async fn update_user(
+    id: i32,
+    session: Session,
+    store: Store,
+    profile: ProfileUpdate,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if session.account_id.0 != id {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+    profile.validate()?;
+
+    let display_name = match profile.display_name {
+        Some(name) => Some(check_profanity(name).await?),
+        None => None,
+    };
+    let bio = match profile.bio {
+        Some(bio) => Some(check_profanity(bio).await?),
+        None => None,
+    };
+    let profile = ProfileUpdate {
+        display_name,
+        bio,
+        avatar_url: profile.avatar_url,
+    };
+
+    match store.update_profile(id, profile).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
@@ -4,20 +4,29 @@
 use std::collections::HashMap;
 
 use tracing::{event, info, instrument, Level};
-use warp::http::StatusCode;
+use warp::http::{
+    header::{HeaderValue, LINK},
+    StatusCode,
+};
+use warp::hyper::body::Bytes;
+use warp::{Filter, Reply};
 
 // use serde::{Deserialize, Serialize};
 
 use crate::store::Store;
-use crate::types::pagination::{extract_pagination, Pagination};
-use crate::types::question::{NewQuestion, Question};
+use crate::types::filter::extract_question_filter;
+use crate::types::pagination::{extract_pagination, link_header, Pagination};
+use crate::types::question::{NewQuestion, QuestionPatch, QuestionStatus, QuestionWithAnswers};
 // use handle_errors::Error;
 use crate::profanity::check_profanity;
+use crate::routes::attachment::fill_attachments;
+use crate::routes::reaction::fill_reactions;
 use crate::types::account::Session;
 
 #[instrument]
 pub async fn get_questions(
     params: HashMap<String, String>,
+    session: Option<Session>,
     store: Store,
     // id: String, LOGGING
 ) -> Result<impl warp::Reply, warp::Rejection> {
@@ -51,11 +60,15 @@ pub async fn get_questions(
     info!("querying questions"); // TRACING
     event!(target: "practical_rust_book", Level::INFO, "querying questions");
     let mut pagination = Pagination::default();
+    let filter = extract_question_filter(&params)?;
 
-    if !params.is_empty() {
+    if params.contains_key("limit")
+        || params.contains_key("offset")
+        || params.contains_key("cursor")
+    {
         // let pagination = extract_pagination(params)?; // BEFORE adding psql
         event!(Level::INFO, pagination = true);
-        pagination = extract_pagination(params)?;
+        pagination = extract_pagination(params.clone())?;
         // log::info!("{} Pagination set {:?}", id, &pagination); LOGGING
         // OLD CODE
         // info!(pagination = true); // TRACING
@@ -64,11 +77,26 @@ pub async fn get_questions(
         // Ok(warp::reply::json(&res))
     }
 
-    match store
-        .get_questions(pagination.limit, pagination.offset)
-        .await
-    {
-        Ok(res) => Ok(warp::reply::json(&res)),
+    match store.clone().get_questions(&pagination, filter).await {
+        Ok(mut res) => {
+            let account_id = session.map(|session| session.account_id);
+            fill_reactions(&store, &mut res.items, &mut [], account_id.as_ref()).await?;
+            fill_attachments(&store, &mut res.items, &mut []).await?;
+
+            let links = link_header(
+                "/questions",
+                &params,
+                &pagination,
+                res.total,
+                res.next_cursor.as_deref(),
+            );
+
+            let mut response = warp::reply::json(&res).into_response();
+            if let Some(value) = links.and_then(|links| HeaderValue::from_str(&links).ok()) {
+                response.headers_mut().insert(LINK, value);
+            }
+            Ok(response)
+        }
         Err(e) => Err(warp::reject::custom(e)),
     }
     // else {
@@ -86,6 +114,33 @@ pub async fn get_questions(
     // }
 }
 
+pub async fn get_question(
+    id: i32,
+    session: Option<Session>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    event!(Level::INFO, question_id = id, "querying question");
+
+    let mut question = store.clone().get_question(id).await?;
+    let mut answers = store.clone().get_answers(id).await?;
+    let account_id = session.map(|session| session.account_id);
+    fill_reactions(
+        &store,
+        std::slice::from_mut(&mut question),
+        &mut answers,
+        account_id.as_ref(),
+    )
+    .await?;
+    fill_attachments(&store, std::slice::from_mut(&mut question), &mut answers).await?;
+
+    match store.get_comments(id).await {
+        Ok(comments) => Ok(warp::reply::json(&QuestionWithAnswers::new(
+            question, answers, comments,
+        ))),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
 pub async fn add_question(
     session: Session,
     store: Store,
@@ -178,10 +233,15 @@ pub async fn update_question(
     id: i32,
     session: Session,
     store: Store,
-    question: Question,
+    question: NewQuestion,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let is_moderator = session.is_moderator();
     let account_id = session.account_id;
-    if store.is_question_owner(id, &account_id).await? {
+    if is_moderator || store.is_question_owner(id, &account_id).await? {
+        if !store.is_question_open(id).await? {
+            return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+        }
+
         let title = check_profanity(question.title);
         // let title = match check_profanity(question.title).await {
         //     Ok(res) => res,
@@ -195,30 +255,23 @@ pub async fn update_question(
         // };
 
         let (title, content) = tokio::join!(title, content);
-        if title.is_ok() && content.is_ok() {
-            // if title.is_err() {
-            //     return Err(warp::reject::custom(title.unwrap_err()));
-            // }
-
-            // if content.is_err() {
-            //     return Err(warp::reject::custom(content.unwrap_err()));
-            // }
-
-            let question = Question {
-                id: question.id,
-                title: title.unwrap(),
-                content: content.unwrap(),
-                tags: question.tags,
-            };
-
-            match store.update_question(question, id, account_id).await {
-                Ok(res) => Ok(warp::reply::json(&res)),
-                Err(e) => Err(warp::reject::custom(e)),
-            }
-        } else {
-            Err(warp::reject::custom(
-                title.expect_err("Expected API call to have failed here."),
-            ))
+        // if title.is_err() {
+        //     return Err(warp::reject::custom(title.unwrap_err()));
+        // }
+
+        // if content.is_err() {
+        //     return Err(warp::reject::custom(content.unwrap_err()));
+        // }
+
+        let question = NewQuestion {
+            title: title?,
+            content: content?,
+            tags: question.tags,
+        };
+
+        match store.update_question(question, id, account_id).await {
+            Ok(res) => Ok(warp::reply::json(&res)),
+            Err(e) => Err(warp::reject::custom(e)),
         }
     } else {
         Err(warp::reject::custom(handle_errors::Error::Unauthorized))
@@ -237,15 +290,75 @@ pub async fn update_question(
     // Ok(warp::reply::with_status("Question udpated", StatusCode::OK))
 }
 
-pub async fn delete_question(
+/// Only the fields present in the patch are changed, and only changed
+/// title or content is sent through the profanity check.
+pub async fn patch_question(
     id: i32,
     session: Session,
     store: Store,
+    patch: QuestionPatch,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+    let is_moderator = session.is_moderator();
     let account_id = session.account_id;
+    if !is_moderator && !store.is_question_owner(id, &account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    let current = store.clone().get_question(id).await?;
+    if !current.status.is_open() {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
 
-    if store.is_question_owner(id, &account_id).await? {
-        match store.delete_question(id, &account_id).await {
+    let title = match patch.title {
+        Some(title) if title != current.title => check_profanity(title).await?,
+        _ => current.title,
+    };
+
+    let content = match patch.content {
+        Some(content) if content != current.content => check_profanity(content).await?,
+        _ => current.content,
+    };
+
+    let question = NewQuestion {
+        title,
+        content,
+        tags: patch.tags.unwrap_or(current.tags),
+    };
+
+    match store.update_question(question, id, account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Body filter for `application/merge-patch+json` requests, which `warp::body::json()` rejects
+pub fn merge_patch_body() -> impl Filter<Extract = (QuestionPatch,), Error = warp::Rejection> + Clone
+{
+    warp::header::<String>("content-type")
+        .and_then(|content_type: String| async move {
+            match content_type.split(';').next().map(str::trim) {
+                Some(mime) if mime.eq_ignore_ascii_case("application/merge-patch+json") => Ok(()),
+                _ => Err(warp::reject::custom(
+                    handle_errors::Error::UnsupportedMediaType,
+                )),
+            }
+        })
+        .untuple_one()
+        .and(warp::body::bytes())
+        .and_then(|body: Bytes| async move {
+            serde_json::from_slice::<QuestionPatch>(&body).map_err(|e| {
+                warp::reject::custom(handle_errors::Error::InvalidParameter(e.to_string()))
+            })
+        })
+}
+
+pub async fn delete_question(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if session.is_moderator() || store.is_question_owner(id, &session.account_id).await? {
+        match store.delete_question(id).await {
             Ok(_) => Ok(warp::reply::with_status(
                 format!("Question {} deleted", id),
                 StatusCode::OK,
@@ -271,3 +384,66 @@ pub async fn delete_question(
     //     None => Err(warp::reject::custom(Error::QuestionNotFound)),
     // }
 }
+
+/// The trash of the logged in account, moderators see the trash of all accounts
+pub async fn get_deleted_questions(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = (!session.is_moderator()).then_some(&session.account_id);
+
+    match store.get_deleted_questions(account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn restore_question(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if session.is_moderator() || store.is_question_owner(id, &session.account_id).await? {
+        match store.restore_question(id).await {
+            Ok(res) => Ok(warp::reply::json(&res)),
+            Err(e) => Err(warp::reject::custom(e)),
+        }
+    } else {
+        Err(warp::reject::custom(handle_errors::Error::Unauthorized))
+    }
+}
+
+pub async fn set_question_status(
+    id: i32,
+    session: Session,
+    store: Store,
+    status: QuestionStatus,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if !session.is_moderator() {
+        if !store.is_question_owner(id, &session.account_id).await? {
+            return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+        }
+
+        // Only moderators lock questions, so owners can neither lock nor unlock them
+        let current = store.clone().get_question(id).await?;
+        if status == QuestionStatus::Locked || current.status == QuestionStatus::Locked {
+            return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+        }
+    }
+
+    if let QuestionStatus::Duplicate { of } = &status {
+        if of.0 == id {
+            return Err(warp::reject::custom(
+                handle_errors::Error::InvalidParameter(
+                    "a question cannot be a duplicate of itself".to_string(),
+                ),
+            ));
+        }
+        store.clone().get_question(of.0).await?;
+    }
+
+    match store.set_question_status(id, status).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
@@ -0,0 +1,120 @@
+use crate::store::Store;
+use crate::types::account::{AccountId, Session};
+use crate::types::answer::{Answer, AnswerId};
+use crate::types::post::Post;
+use crate::types::question::{Question, QuestionId};
+use crate::types::reaction::Reaction;
+
+pub async fn add_question_reaction(
+    id: i32,
+    reaction: Reaction,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_question(id).await?;
+
+    toggle_reaction(
+        Post::Question(QuestionId(id)),
+        reaction,
+        true,
+        session,
+        store,
+    )
+    .await
+}
+
+pub async fn remove_question_reaction(
+    id: i32,
+    reaction: Reaction,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    toggle_reaction(
+        Post::Question(QuestionId(id)),
+        reaction,
+        false,
+        session,
+        store,
+    )
+    .await
+}
+
+pub async fn add_answer_reaction(
+    id: i32,
+    reaction: Reaction,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_answer(id).await?;
+
+    toggle_reaction(Post::Answer(AnswerId(id)), reaction, true, session, store).await
+}
+
+pub async fn remove_answer_reaction(
+    id: i32,
+    reaction: Reaction,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    toggle_reaction(Post::Answer(AnswerId(id)), reaction, false, session, store).await
+}
+
+/// Turns the reaction of the account on or off, and returns the new counts of the post
+async fn toggle_reaction(
+    post: Post,
+    reaction: Reaction,
+    on: bool,
+    session: Session,
+    store: Store,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    let account_id = session.account_id;
+
+    if on {
+        store
+            .clone()
+            .add_reaction(&account_id, &post, reaction)
+            .await?;
+    } else {
+        store
+            .clone()
+            .remove_reaction(&account_id, &post, reaction)
+            .await?;
+    }
+
+    match store
+        .get_reactions(std::slice::from_ref(&post), Some(&account_id))
+        .await
+    {
+        Ok(mut res) => Ok(warp::reply::json(&res.remove(&post).unwrap_or_default())),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Sets `reactions` on all given questions and answers with a single query
+pub async fn fill_reactions(
+    store: &Store,
+    questions: &mut [Question],
+    answers: &mut [Answer],
+    account_id: Option<&AccountId>,
+) -> Result<(), handle_errors::Error> {
+    let posts = questions
+        .iter()
+        .map(|question| Post::Question(question.id.clone()))
+        .chain(answers.iter().map(|answer| Post::Answer(answer.id.clone())))
+        .collect::<Vec<Post>>();
+    if posts.is_empty() {
+        return Ok(());
+    }
+
+    let mut reactions = store.clone().get_reactions(&posts, account_id).await?;
+    for question in questions.iter_mut() {
+        let post = Post::Question(question.id.clone());
+        question.reactions = reactions.remove(&post).unwrap_or_default();
+    }
+    for answer in answers.iter_mut() {
+        let post = Post::Answer(answer.id.clone());
+        answer.reactions = reactions.remove(&post).unwrap_or_default();
+    }
+
+    Ok(())
+}
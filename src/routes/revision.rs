@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use tracing::{event, Level};
+
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::question::NewQuestion;
+
+pub async fn get_revisions(
+    question_id: i32,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    event!(Level::INFO, question_id, "querying revisions");
+
+    // Revisions of deleted questions are not visible either
+    store.clone().get_question(question_id).await?;
+
+    match store.get_revisions(question_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Compares two revisions of a question given as '?from=1&to=2'
+pub async fn get_revision_diff(
+    question_id: i32,
+    params: HashMap<String, String>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let (from, to) = match (params.get("from"), params.get("to")) {
+        (Some(from), Some(to)) => (
+            from.parse::<i32>()
+                .map_err(handle_errors::Error::ParseError)?,
+            to.parse::<i32>()
+                .map_err(handle_errors::Error::ParseError)?,
+        ),
+        _ => {
+            return Err(warp::reject::custom(
+                handle_errors::Error::MissingParameters,
+            ))
+        }
+    };
+
+    store.clone().get_question(question_id).await?;
+
+    let from = store.clone().get_revision(question_id, from).await?;
+    let to = store.get_revision(question_id, to).await?;
+
+    Ok(warp::reply::json(&from.diff(&to)))
+}
+
+/// Restores the title, content and tags of an earlier revision, which is recorded as a new revision
+pub async fn rollback_revision(
+    question_id: i32,
+    revision_id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id.clone();
+
+    if !session.is_moderator() && !store.is_question_owner(question_id, &account_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::Unauthorized));
+    }
+
+    if !store.is_question_open(question_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
+    let revision = store.clone().get_revision(question_id, revision_id).await?;
+
+    let question = NewQuestion {
+        title: revision.title,
+        content: revision.content,
+        tags: revision.tags,
+    };
+
+    match store
+        .update_question(question, question_id, account_id)
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
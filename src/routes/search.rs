@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use tracing::{event, instrument, Level};
+
+use crate::store::Store;
+use crate::types::pagination::{extract_pagination, Pagination};
+
+#[instrument]
+pub async fn search(
+    params: HashMap<String, String>,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let query = match params.get("q").map(|q| q.trim()) {
+        Some(q) if !q.is_empty() => q.to_string(),
+        _ => {
+            return Err(warp::reject::custom(
+                handle_errors::Error::MissingParameters,
+            ))
+        }
+    };
+
+    event!(Level::INFO, query = %query, "searching questions");
+    let mut pagination = Pagination::default();
+
+    if params.contains_key("limit") || params.contains_key("offset") {
+        event!(Level::INFO, pagination = true);
+        pagination = extract_pagination(params)?;
+    }
+
+    match store
+        .search(query, pagination.limit, pagination.offset)
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
@@ -0,0 +1,33 @@
+use crate::routes::authentication::revoke_token;
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::session::{SessionId, ACCESS_TOKEN_MINUTES};
+
+pub async fn get_sessions(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    match store.get_sessions(&session.account_id, &session.jti).await {
+        Ok(sessions) => Ok(warp::reply::json(&sessions)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Ends one of the sessions of the account, e.g. on a lost device
+pub async fn delete_session(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let revoked = store
+        .revoke_session(
+            &session.account_id,
+            Some(&SessionId(id)),
+            None,
+            ACCESS_TOKEN_MINUTES,
+        )
+        .await?;
+    revoke_token(revoked);
+
+    Ok(warp::reply::json(&format!("Session {} ended", id)))
+}
@@ -0,0 +1,226 @@
+use crate::profanity::check_profanity;
+use crate::store::Store;
+use crate::types::account::{AccountId, Session};
+use crate::types::answer::{AnswerId, AnswerUpdate};
+use crate::types::post::Post;
+use crate::types::question::{NewQuestion, QuestionId};
+use crate::types::suggested_edit::{EditStatus, SuggestedEdit, SuggestedEditUpdate};
+
+pub async fn suggest_question_edit(
+    id: i32,
+    session: Session,
+    store: Store,
+    question: NewQuestion,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+
+    if !store.is_question_open(id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+    if store.is_question_owner(id, &account_id).await? {
+        return Err(warp::reject::custom(
+            handle_errors::Error::CannotSuggestEditOnOwnPost,
+        ));
+    }
+
+    let (title, content) = tokio::join!(
+        check_profanity(question.title),
+        check_profanity(question.content)
+    );
+
+    match store
+        .add_suggested_edit(
+            &Post::Question(QuestionId(id)),
+            Some(title?),
+            content?,
+            question.tags,
+            account_id,
+        )
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn suggest_answer_edit(
+    id: i32,
+    session: Session,
+    store: Store,
+    answer: AnswerUpdate,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+
+    let current = store.clone().get_answer(id).await?;
+    if !store.is_question_open(current.question_id.0).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+    if store.is_answer_owner(id, &account_id).await? {
+        return Err(warp::reject::custom(
+            handle_errors::Error::CannotSuggestEditOnOwnPost,
+        ));
+    }
+
+    let content = check_profanity(answer.content).await?;
+
+    match store
+        .add_suggested_edit(&Post::Answer(AnswerId(id)), None, content, None, account_id)
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Pending edits others suggested to the questions and answers of the logged in account,
+/// moderators see the pending edits to all posts
+pub async fn get_suggested_edits(
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = (!session.is_moderator()).then_some(&session.account_id);
+
+    match store.get_pending_edits(account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn approve_suggested_edit(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let edit = reviewable_edit(id, &session, &store).await?;
+
+    apply_edit(edit, &session.account_id, None, store).await
+}
+
+/// Approves the edit with the reviewer's changes instead of the suggested ones
+pub async fn improve_suggested_edit(
+    id: i32,
+    session: Session,
+    store: Store,
+    update: SuggestedEditUpdate,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let edit = reviewable_edit(id, &session, &store).await?;
+
+    let title = match (edit.post(), update.title) {
+        (Post::Question(_), Some(title)) => Some(check_profanity(title).await?),
+        (Post::Question(_), None) => {
+            return Err(warp::reject::custom(
+                handle_errors::Error::InvalidParameter(String::from(
+                    "title is required for edits to questions",
+                )),
+            ))
+        }
+        (Post::Answer(_), None) if update.tags.is_none() => None,
+        (Post::Answer(_), _) => {
+            return Err(warp::reject::custom(
+                handle_errors::Error::InvalidParameter(String::from(
+                    "answers have no title or tags",
+                )),
+            ))
+        }
+    };
+    let improvement = SuggestedEditUpdate {
+        title,
+        content: check_profanity(update.content).await?,
+        tags: update.tags,
+    };
+
+    apply_edit(edit, &session.account_id, Some(improvement), store).await
+}
+
+pub async fn reject_suggested_edit(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reviewable_edit(id, &session, &store).await?;
+
+    match store.reject_suggested_edit(id, &session.account_id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_question_contributors(
+    id: i32,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_question(id).await?;
+
+    match store
+        .get_contributors(&Post::Question(QuestionId(id)))
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn get_answer_contributors(
+    id: i32,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    store.clone().get_answer(id).await?;
+
+    match store.get_contributors(&Post::Answer(AnswerId(id))).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+/// Loads a pending edit the session is allowed to review, which is the case when its account
+/// owns the post or it belongs to a moderator
+async fn reviewable_edit(
+    id: i32,
+    session: &Session,
+    store: &Store,
+) -> Result<SuggestedEdit, handle_errors::Error> {
+    let edit = store.clone().get_suggested_edit(id).await?;
+    if edit.status != EditStatus::Pending {
+        return Err(handle_errors::Error::SuggestedEditAlreadyReviewed);
+    }
+
+    if session.is_moderator() {
+        return Ok(edit);
+    }
+
+    let account_id = &session.account_id;
+    let is_owner = match edit.post() {
+        Post::Question(question_id) => store.is_question_owner(question_id.0, account_id).await?,
+        Post::Answer(answer_id) => store.is_answer_owner(answer_id.0, account_id).await?,
+    };
+    if !is_owner {
+        return Err(handle_errors::Error::Unauthorized);
+    }
+
+    Ok(edit)
+}
+
+/// Applies the edit like an update by the suggester would, or the reviewer's improvement of it,
+/// and marks it approved
+async fn apply_edit(
+    edit: SuggestedEdit,
+    reviewer_id: &AccountId,
+    improvement: Option<SuggestedEditUpdate>,
+    store: Store,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    let question_id = match edit.post() {
+        Post::Question(question_id) => question_id.0,
+        Post::Answer(answer_id) => store.clone().get_answer(answer_id.0).await?.question_id.0,
+    };
+    if !store.is_question_open(question_id).await? {
+        return Err(warp::reject::custom(handle_errors::Error::QuestionClosed));
+    }
+
+    match store
+        .approve_suggested_edit(edit.id.0, reviewer_id, improvement)
+        .await
+    {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::mailer::{Email, Mailer};
+use crate::routes::authentication::{issue_verification_token, verify_verification_token};
+use crate::store::Store;
+use crate::types::account::{
+    AccountId, Session, VerificationQuery, VERIFICATION_RESEND_MINUTES, VERIFICATION_TOKEN_HOURS,
+};
+
+/// Emails a link to `GET /verification` in the background, failures are only logged
+pub fn send_verification_email(
+    mailer: Arc<dyn Mailer>,
+    public_url: &str,
+    account_id: &AccountId,
+    to: String,
+) {
+    let token = issue_verification_token(account_id, &to);
+    let email = Email {
+        subject: String::from("Verify your email"),
+        body: format!(
+            "Open this link to verify your email and start posting:\n{}/verification?token={}\n\nThe link expires in {} hours.",
+            public_url.trim_end_matches('/'),
+            token,
+            VERIFICATION_TOKEN_HOURS
+        ),
+        to,
+    };
+
+    tokio::spawn(async move {
+        if let Err(e) = mailer.send(email).await {
+            tracing::event!(tracing::Level::ERROR, "{:?}", e);
+        }
+    });
+}
+
+pub async fn verify_email(
+    query: VerificationQuery,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let verification = verify_verification_token(&query.token)?;
+
+    match store
+        .verify_email(&verification.account_id, &verification.email)
+        .await
+    {
+        Ok(_) => Ok(warp::reply::json(&"Email verified")),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn resend_verification(
+    session: Session,
+    store: Store,
+    mailer: Arc<dyn Mailer>,
+    public_url: String,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let email = store
+        .claim_verification_resend(&session.account_id, VERIFICATION_RESEND_MINUTES)
+        .await?;
+
+    send_verification_email(mailer, &public_url, &session.account_id, email);
+
+    Ok(warp::reply::with_status(
+        warp::reply::json(&"Verification email sent"),
+        warp::http::StatusCode::ACCEPTED,
+    ))
+}
@@ -0,0 +1,106 @@
+use crate::store::Store;
+use crate::types::account::Session;
+use crate::types::answer::AnswerId;
+use crate::types::post::Post;
+use crate::types::question::QuestionId;
+use crate::types::vote::NewVote;
+
+pub async fn vote_question(
+    id: i32,
+    session: Session,
+    store: Store,
+    vote: NewVote,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+    validate_vote(&vote)?;
+
+    store.clone().get_question(id).await?;
+    if store.is_question_owner(id, &account_id).await? {
+        return Err(warp::reject::custom(
+            handle_errors::Error::CannotVoteOnOwnPost,
+        ));
+    }
+
+    let post = Post::Question(QuestionId(id));
+    store
+        .clone()
+        .cast_vote(&account_id, &post, vote.value)
+        .await?;
+
+    match store.get_question(id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn retract_question_vote(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let post = Post::Question(QuestionId(id));
+    store
+        .clone()
+        .retract_vote(&session.account_id, &post)
+        .await?;
+
+    match store.get_question(id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn vote_answer(
+    id: i32,
+    session: Session,
+    store: Store,
+    vote: NewVote,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let account_id = session.account_id;
+    validate_vote(&vote)?;
+
+    store.clone().get_answer(id).await?;
+    if store.is_answer_owner(id, &account_id).await? {
+        return Err(warp::reject::custom(
+            handle_errors::Error::CannotVoteOnOwnPost,
+        ));
+    }
+
+    let post = Post::Answer(AnswerId(id));
+    store
+        .clone()
+        .cast_vote(&account_id, &post, vote.value)
+        .await?;
+
+    match store.get_answer(id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+pub async fn retract_answer_vote(
+    id: i32,
+    session: Session,
+    store: Store,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let post = Post::Answer(AnswerId(id));
+    store
+        .clone()
+        .retract_vote(&session.account_id, &post)
+        .await?;
+
+    match store.get_answer(id).await {
+        Ok(res) => Ok(warp::reply::json(&res)),
+        Err(e) => Err(warp::reject::custom(e)),
+    }
+}
+
+fn validate_vote(vote: &NewVote) -> Result<(), handle_errors::Error> {
+    match vote.value {
+        1 | -1 => Ok(()),
+        other => Err(handle_errors::Error::InvalidParameter(format!(
+            "vote value must be 1 or -1, got {}",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use handle_errors::Error;
+
+use super::Storage;
+
+/// Keeps files in a directory on the local filesystem
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        LocalStorage {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf, Error> {
+        // Keys are generated by us, this only guards against leaving the root directory
+        if key
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "..")
+        {
+            return Err(Error::StorageError(format!("invalid key '{}'", key)));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(&self, key: &str, _content_type: &str, data: Bytes) -> Result<(), Error> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| Error::StorageError(e.to_string()))?;
+        }
+
+        tokio::fs::write(path, data)
+            .await
+            .map_err(|e| Error::StorageError(e.to_string()))
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, Error> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(Bytes::from(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(Error::AttachmentNotFound),
+            Err(e) => Err(Error::StorageError(e.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(Error::StorageError(e.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod local_storage_tests {
+    use super::{Bytes, LocalStorage, Storage};
+
+    #[tokio::test]
+    async fn put_get_delete() {
+        let root = std::env::temp_dir().join(format!("local_storage_{}", uuid::Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+
+        storage
+            .put("attachments/a", "text/plain", Bytes::from("log line"))
+            .await
+            .unwrap();
+        assert_eq!(
+            storage.get("attachments/a").await.unwrap(),
+            Bytes::from("log line")
+        );
+
+        storage.delete("attachments/a").await.unwrap();
+        assert!(storage.get("attachments/a").await.is_err());
+        assert!(storage.get("../a").await.is_err());
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+}
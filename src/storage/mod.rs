@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use handle_errors::Error;
+
+use crate::config::Config;
+
+mod local;
+mod s3;
+
+pub use self::local::LocalStorage;
+pub use self::s3::S3Storage;
+
+/// Backend the attachment files are kept in, keyed by the `storage_key` of the attachment
+#[async_trait]
+pub trait Storage: std::fmt::Debug + Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<(), Error>;
+    async fn get(&self, key: &str) -> Result<Bytes, Error>;
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+}
+
+/// The storage backend together with the limits that apply to uploads
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    pub backend: Arc<dyn Storage>,
+    /// Largest accepted file in bytes
+    pub max_file_size: u64,
+    /// Total bytes a single account can have stored
+    pub account_quota: i64,
+}
+
+impl FileStore {
+    pub fn new(backend: Arc<dyn Storage>, max_file_size: u64, account_quota: i64) -> Self {
+        FileStore {
+            backend,
+            max_file_size,
+            account_quota,
+        }
+    }
+
+    /// Picks the backend set by `storage_backend`, either `local` or `s3`
+    pub fn from_config(config: &Config) -> Result<Self, Error> {
+        let backend: Arc<dyn Storage> = match config.storage_backend.as_str() {
+            "local" => Arc::new(LocalStorage::new(&config.storage_path)),
+            "s3" => Arc::new(S3Storage::new(
+                &config.s3_bucket,
+                &config.s3_region,
+                &config.s3_endpoint,
+                &config.s3_access_key,
+                &config.s3_secret_key,
+            )?),
+            other => {
+                return Err(Error::InvalidParameter(format!(
+                    "storage backend must be 'local' or 's3', got '{}'",
+                    other
+                )))
+            }
+        };
+
+        Ok(FileStore::new(
+            backend,
+            config.max_attachment_size,
+            config.attachment_quota,
+        ))
+    }
+
+    /// Deletes the files of attachments that were removed from the database. Nothing refers
+    /// to them anymore, so failures only leave orphaned files and are just logged.
+    pub async fn delete_files(&self, storage_keys: &[String]) {
+        for key in storage_keys {
+            if let Err(e) = self.backend.delete(key).await {
+                tracing::event!(tracing::Level::ERROR, "{}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use s3::{creds::Credentials, Bucket, Region};
+
+use handle_errors::Error;
+
+use super::Storage;
+
+/// Keeps files in a bucket of an S3 compatible API, like AWS or a local MinIO
+#[derive(Debug, Clone)]
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    pub fn new(
+        bucket: &str,
+        region: &str,
+        endpoint: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<Self, Error> {
+        let region = Region::Custom {
+            region: region.to_string(),
+            endpoint: endpoint.to_string(),
+        };
+        let credentials = Credentials::new(Some(access_key), Some(secret_key), None, None, None)
+            .map_err(|e| Error::StorageError(e.to_string()))?;
+
+        // MinIO only supports path style requests by default
+        let bucket = Bucket::new(bucket, region, credentials)
+            .map_err(|e| Error::StorageError(e.to_string()))?
+            .with_path_style();
+
+        Ok(S3Storage { bucket })
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, data: Bytes) -> Result<(), Error> {
+        match self
+            .bucket
+            .put_object_with_content_type(key, &data, content_type)
+            .await
+        {
+            Ok(response) if response.status_code() < 300 => Ok(()),
+            Ok(response) => Err(Error::StorageError(format!(
+                "upload of {} failed with status {}",
+                key,
+                response.status_code()
+            ))),
+            Err(e) => Err(Error::StorageError(e.to_string())),
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, Error> {
+        match self.bucket.get_object(key).await {
+            Ok(response) if response.status_code() == 404 => Err(Error::AttachmentNotFound),
+            Ok(response) if response.status_code() < 300 => Ok(response.bytes().clone()),
+            Ok(response) => Err(Error::StorageError(format!(
+                "download of {} failed with status {}",
+                key,
+                response.status_code()
+            ))),
+            Err(e) => Err(Error::StorageError(e.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        match self.bucket.delete_object(key).await {
+            Ok(response) if response.status_code() < 300 || response.status_code() == 404 => Ok(()),
+            Ok(response) => Err(Error::StorageError(format!(
+                "deletion of {} failed with status {}",
+                key,
+                response.status_code()
+            ))),
+            Err(e) => Err(Error::StorageError(e.to_string())),
+        }
+    }
+}
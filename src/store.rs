@@ -1,16 +1,31 @@
 // use serde_json::error;
-// use std::collections::HashMap;
+use std::collections::HashMap;
+use std::future::Future;
 // use std::sync::Arc;
 use sqlx::postgres::{PgPool, PgPoolOptions, PgRow};
-use sqlx::Row;
+use sqlx::{Postgres, QueryBuilder, Row, Transaction};
 // use tokio::sync::RwLock;
 
 use handle_errors::Error;
 
 use crate::types::{
-    account::{Account, AccountId},
+    account::{Account, AccountId, AccountSummary, Role},
     answer::{Answer, AnswerId, NewAnswer},
-    question::{NewQuestion, Question, QuestionId},
+    attachment::{Attachment, AttachmentId, NewAttachment},
+    bounty::{Bounty, BountyId, BountyStatus, NewBounty},
+    comment::{Comment, CommentId},
+    draft::{Draft, DraftContent, DraftId},
+    filter::{AnswerFilter, QuestionFilter, SortOrder, TagMatch},
+    pagination::{Cursor, Pagination},
+    points::{LedgerEntry, LedgerReason, Points},
+    post::Post,
+    profile::{Profile, ProfileUpdate},
+    question::{DeletedQuestion, NewQuestion, Question, QuestionId, QuestionPage, QuestionStatus},
+    reaction::{Reaction, ReactionCount},
+    revision::{Revision, RevisionId},
+    search::SearchResult,
+    session::{ActiveSession, SessionId},
+    suggested_edit::{EditStatus, SuggestedEdit, SuggestedEditId, SuggestedEditUpdate},
 };
 
 #[derive(Debug, Clone)]
@@ -58,22 +73,240 @@ impl Store {
 
     pub async fn get_questions(
         self,
-        limit: Option<i32>,
-        offset: i32,
-    ) -> Result<Vec<Question>, Error> {
-        match sqlx::query("SELECT * from questions LIMIT $1 OFFSET $2")
-            .bind(limit)
-            .bind(offset)
-            .map(|row: PgRow| Question {
-                id: QuestionId(row.get("id")),
-                title: row.get("title"),
-                content: row.get("content"),
-                tags: row.get("tags"),
+        pagination: &Pagination,
+        filter: QuestionFilter,
+    ) -> Result<QuestionPage, Error> {
+        let mut count_query = QueryBuilder::<Postgres>::new(
+            "SELECT COUNT(*) from questions WHERE deleted_at IS NULL",
+        );
+        push_question_filter(&mut count_query, &filter);
+
+        let total = match count_query
+            .build()
+            .map(|row: PgRow| row.get::<i64, _>(0))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(total) => total,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        let mut query =
+            QueryBuilder::<Postgres>::new("SELECT * from questions WHERE deleted_at IS NULL");
+        push_question_filter(&mut query, &filter);
+
+        if let Some(cursor) = &pagination.cursor {
+            match filter.sort {
+                SortOrder::Newest => query.push(" AND (created_on, id) < ("),
+                SortOrder::Oldest => query.push(" AND (created_on, id) > ("),
+                _ => {
+                    return Err(Error::InvalidParameter(
+                        "cursor can only be used when sorting by newest or oldest".to_string(),
+                    ))
+                }
+            };
+            query
+                .push_bind(cursor.created_on)
+                .push(", ")
+                .push_bind(cursor.id)
+                .push(")");
+        }
+
+        query.push(" ORDER BY ").push(filter.sort.order_by());
+        query.push(" LIMIT ").push_bind(pagination.limit);
+        query.push(" OFFSET ").push_bind(pagination.offset);
+
+        match query
+            .build()
+            .map(|row: PgRow| {
+                (
+                    question_from_row(&row),
+                    Cursor {
+                        created_on: row.get("created_on"),
+                        id: row.get("id"),
+                    },
+                )
             })
             .fetch_all(&self.connection)
             .await
         {
-            Ok(questions) => Ok(questions),
+            Ok(rows) => {
+                // A full page means there might be more questions after the last one
+                let next_cursor = match pagination.limit {
+                    Some(limit)
+                        if filter.sort.supports_cursor() && rows.len() == limit as usize =>
+                    {
+                        rows.last().map(|(_, cursor)| cursor.encode())
+                    }
+                    _ => None,
+                };
+
+                Ok(QuestionPage {
+                    items: rows.into_iter().map(|(question, _)| question).collect(),
+                    total,
+                    limit: pagination.limit,
+                    offset: match pagination.cursor {
+                        Some(_) => None,
+                        None => Some(pagination.offset),
+                    },
+                    next_cursor,
+                })
+            }
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_question(self, id: i32) -> Result<Question, Error> {
+        match sqlx::query("SELECT * from questions WHERE id = $1 AND deleted_at IS NULL")
+            .bind(id)
+            .map(|row: PgRow| question_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(question)) => Ok(question),
+            Ok(None) => Err(Error::QuestionNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn set_question_status(
+        self,
+        id: i32,
+        status: QuestionStatus,
+    ) -> Result<Question, Error> {
+        let (status, close_reason, duplicate_of) = match status {
+            QuestionStatus::Open => ("open", None, None),
+            QuestionStatus::Closed { reason } => ("closed", Some(reason), None),
+            QuestionStatus::Duplicate { of } => ("duplicate", None, Some(of.0)),
+            QuestionStatus::Locked => ("locked", None, None),
+        };
+
+        match sqlx::query(
+            "UPDATE questions SET status = $1, close_reason = $2, duplicate_of = $3 WHERE id = $4 AND deleted_at IS NULL RETURNING *",
+        )
+        .bind(status)
+        .bind(close_reason)
+        .bind(duplicate_of)
+        .bind(id)
+        .map(|row: PgRow| question_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(question)) => Ok(question),
+            Ok(None) => Err(Error::QuestionNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Marks one of the answers to the question as accepted, or unmarks it with `None`
+    pub async fn set_accepted_answer(
+        self,
+        question_id: i32,
+        answer_id: Option<AnswerId>,
+    ) -> Result<Question, Error> {
+        match sqlx::query(
+            "UPDATE questions SET accepted_answer_id = $1 WHERE id = $2 AND deleted_at IS NULL RETURNING *",
+        )
+        .bind(answer_id.map(|id| id.0))
+        .bind(question_id)
+        .map(|row: PgRow| question_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(question)) => Ok(question),
+            Ok(None) => Err(Error::QuestionNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_answers(self, question_id: i32) -> Result<Vec<Answer>, Error> {
+        match sqlx::query(&format!(
+            "SELECT {} from answers WHERE corresponding_question = $1 ORDER BY is_accepted DESC, score DESC, created_on, id",
+            ANSWER_COLUMNS
+        ))
+        .bind(question_id)
+        .map(|row: PgRow| answer_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(answers) => Ok(answers),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn search(
+        self,
+        query: String,
+        limit: Option<i32>,
+        offset: i32,
+    ) -> Result<Vec<SearchResult>, Error> {
+        match sqlx::query(
+            "WITH query AS (SELECT websearch_to_tsquery('english', $1) AS q),
+            hits AS (
+                SELECT questions.id AS question_id FROM questions, query
+                WHERE questions.search_vector @@ query.q
+                UNION
+                SELECT answers.corresponding_question FROM answers, query
+                WHERE answers.search_vector @@ query.q
+            )
+            SELECT questions.id, questions.title,
+                ts_headline('english', html_escape(questions.title), query.q,
+                    'StartSel=<mark>, StopSel=</mark>, HighlightAll=true') AS highlighted_title,
+                CASE WHEN questions.search_vector @@ query.q
+                    THEN ts_headline('english', html_escape(questions.content), query.q,
+                        'StartSel=<mark>, StopSel=</mark>, MaxFragments=2')
+                    ELSE ts_headline('english', html_escape(answer.content), query.q,
+                        'StartSel=<mark>, StopSel=</mark>, MaxFragments=2')
+                END AS snippet,
+                CASE WHEN questions.search_vector @@ query.q THEN NULL ELSE answer.id END AS answer_id,
+                ts_rank(questions.search_vector, query.q) + coalesce(answer.rank, 0) AS rank
+            FROM hits
+            JOIN questions ON questions.id = hits.question_id AND questions.deleted_at IS NULL
+            CROSS JOIN query
+            LEFT JOIN LATERAL (
+                SELECT answers.id, answers.content, ts_rank(answers.search_vector, query.q) AS rank
+                FROM answers
+                WHERE answers.corresponding_question = questions.id
+                AND answers.search_vector @@ query.q
+                ORDER BY rank DESC
+                LIMIT 1
+            ) answer ON TRUE
+            ORDER BY rank DESC, questions.id
+            LIMIT $2 OFFSET $3",
+        )
+        .bind(query)
+        .bind(limit)
+        .bind(offset)
+        .map(|row: PgRow| SearchResult {
+            question_id: QuestionId(row.get("id")),
+            title: row.get("title"),
+            highlighted_title: row.get("highlighted_title"),
+            snippet: row.get("snippet"),
+            answer_id: row.get::<Option<i32>, _>("answer_id").map(AnswerId),
+            rank: row.get("rank"),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(results) => Ok(results),
             Err(error) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
@@ -86,21 +319,52 @@ impl Store {
         new_question: NewQuestion,
         account_id: AccountId,
     ) -> Result<Question, Error> {
-        match sqlx::query("INSERT INTO questions (title, content, tags, account_id) VALUES ($1, $2, $3, $4) RETURNING id, title, content, tags")
-            .bind(new_question.title)
-            .bind(new_question.content)
-            .bind(new_question.tags)
-            .bind(account_id.0)
-            .map(|row: PgRow| Question {
-                id: QuestionId(row.get("id")),
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+        let question = save_new_question(&mut tx, new_question, &account_id).await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(question)
+    }
+
+    /// Overwrites the question and records the new state as a revision made by `account_id`.
+    /// Callers have to make sure the account is allowed to edit the question.
+    pub async fn update_question(
+        self,
+        question: NewQuestion,
+        id: i32,
+        account_id: AccountId,
+    ) -> Result<Question, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+        let question = save_question_update(&mut tx, question, id, &account_id).await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(question)
+    }
+
+    pub async fn get_revisions(self, question_id: i32) -> Result<Vec<Revision>, Error> {
+        match sqlx::query("SELECT * from question_revisions WHERE question_id = $1 ORDER BY id")
+            .bind(question_id)
+            .map(|row: PgRow| Revision {
+                id: RevisionId(row.get("id")),
+                question_id: QuestionId(row.get("question_id")),
                 title: row.get("title"),
                 content: row.get("content"),
                 tags: row.get("tags"),
+                account_id: AccountId(row.get("account_id")),
+                created_on: row.get("created_on"),
             })
-            .fetch_one(&self.connection)
+            .fetch_all(&self.connection)
             .await
         {
-            Ok(question) => Ok(question),
+            Ok(revisions) => Ok(revisions),
             Err(error) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
@@ -108,29 +372,70 @@ impl Store {
         }
     }
 
-    pub async fn update_question(
-        self,
-        question: Question,
-        id: i32,
-        account_id: AccountId,
-    ) -> Result<Question, Error> {
-        println!("{}", account_id.0);
+    pub async fn get_revision(self, question_id: i32, id: i32) -> Result<Revision, Error> {
+        match sqlx::query("SELECT * from question_revisions WHERE id = $1 AND question_id = $2")
+            .bind(id)
+            .bind(question_id)
+            .map(|row: PgRow| Revision {
+                id: RevisionId(row.get("id")),
+                question_id: QuestionId(row.get("question_id")),
+                title: row.get("title"),
+                content: row.get("content"),
+                tags: row.get("tags"),
+                account_id: AccountId(row.get("account_id")),
+                created_on: row.get("created_on"),
+            })
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(revision)) => Ok(revision),
+            Ok(None) => Err(Error::RevisionNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
 
-        match sqlx::query("UPDATE questions SET title = $1, content = $2, tags = $3 WHERE id = $4 AND account_id = $5 RETURNING id, table, content, tags")
-        .bind(question.title)
-        .bind(question.content)
-        .bind(question.tags)
+    /// Moves the question to the trash of its owner, from where it can be restored until it gets purged
+    ///
+    /// Callers check ownership, since moderators may delete questions of other accounts
+    pub async fn delete_question(self, id: i32) -> Result<bool, Error> {
+        match sqlx::query(
+            "UPDATE questions SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL",
+        )
         .bind(id)
-        .bind(account_id.0)
-        .map(|row: PgRow| Question {
-            id: QuestionId(row.get("id")),
-            title: row.get("title"),
-            content: row.get("content"),
-            tags: row.get("tags"),
+        .execute(&self.connection)
+        .await
+        {
+            Ok(result) if result.rows_affected() == 0 => Err(Error::QuestionNotFound),
+            Ok(_) => Ok(true),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Trashed questions of the given account, or of all accounts when none is given
+    pub async fn get_deleted_questions(
+        self,
+        account_id: Option<&AccountId>,
+    ) -> Result<Vec<DeletedQuestion>, Error> {
+        match sqlx::query(
+            "SELECT * from questions
+            WHERE ($1::integer IS NULL OR account_id = $1) AND deleted_at IS NOT NULL
+            ORDER BY deleted_at DESC",
+        )
+        .bind(account_id.map(|account_id| account_id.0))
+        .map(|row: PgRow| DeletedQuestion {
+            question: question_from_row(&row),
+            deleted_at: row.get("deleted_at"),
         })
-        .fetch_one(&self.connection)
-        .await {
-            Ok(question) => Ok(question),
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(questions) => Ok(questions),
             Err(error) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
@@ -138,14 +443,17 @@ impl Store {
         }
     }
 
-    pub async fn delete_question(self, id: i32, account_id: &AccountId) -> Result<bool, Error> {
-        match sqlx::query("DELETE from questions WHERE id = $1 AND account_id = $2")
-            .bind(id)
-            .bind(account_id.0)
-            .execute(&self.connection)
-            .await
+    pub async fn restore_question(self, id: i32) -> Result<Question, Error> {
+        match sqlx::query(
+            "UPDATE questions SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL RETURNING *",
+        )
+        .bind(id)
+        .map(|row: PgRow| question_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
         {
-            Ok(_) => Ok(true),
+            Ok(Some(question)) => Ok(question),
+            Ok(None) => Err(Error::QuestionNotFound),
             Err(error) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
@@ -153,22 +461,85 @@ impl Store {
         }
     }
 
+    /// Permanently removes questions, and their answers, that have been in the trash
+    /// for longer than the retention period. Returns the number of purged questions and the
+    /// storage keys of their attachments, whose files are up to the caller to delete.
+    pub async fn purge_deleted_questions(
+        self,
+        retention_days: i32,
+    ) -> Result<(u64, Vec<String>), Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let storage_keys = match sqlx::query(
+            "DELETE from attachments WHERE question_id IN (
+                SELECT id from questions WHERE deleted_at < NOW() - make_interval(days => $1)
+            ) OR answer_id IN (
+                SELECT answers.id from answers
+                JOIN questions ON questions.id = answers.corresponding_question
+                WHERE questions.deleted_at < NOW() - make_interval(days => $1)
+            ) RETURNING storage_key",
+        )
+        .bind(retention_days)
+        .map(|row: PgRow| row.get::<String, _>("storage_key"))
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(storage_keys) => storage_keys,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        if let Err(error) = sqlx::query(
+            "DELETE from answers WHERE corresponding_question IN (
+                SELECT id from questions WHERE deleted_at < NOW() - make_interval(days => $1)
+            )",
+        )
+        .bind(retention_days)
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        let purged = match sqlx::query(
+            "DELETE from questions WHERE deleted_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(retention_days)
+        .execute(&mut *tx)
+        .await
+        {
+            Ok(result) => result.rows_affected(),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok((purged, storage_keys))
+    }
+
     pub async fn add_answer(
         self,
         new_answer: NewAnswer,
         account_id: AccountId,
     ) -> Result<Answer, Error> {
-        match sqlx::query(
-            "INSERT INTO answers (content, corresponding_question, account_id) VALUES ($1, $2, $3)",
-        )
+        match sqlx::query(&format!(
+            "INSERT INTO answers (content, corresponding_question, account_id) VALUES ($1, $2, $3) RETURNING {}",
+            ANSWER_COLUMNS
+        ))
         .bind(new_answer.content)
         .bind(new_answer.question_id.0)
         .bind(account_id.0)
-        .map(|row: PgRow| Answer {
-            id: AnswerId(row.get("id")),
-            content: row.get("content"),
-            question_id: QuestionId(row.get("question_id")),
-        })
+        .map(|row: PgRow| answer_from_row(&row))
         .fetch_one(&self.connection)
         .await
         {
@@ -190,59 +561,91 @@ impl Store {
             }
         }
     }
-    // fn add_question(mut self, question: Question) -> Self {
-    //     self.questions.insert(question.id.clone(), question);
-    //     self
-    // }
-
-    // fn init(self) -> Self {
-    //     let question = Question::new(
-    //         QuestionId::from_str("1").expect("Id not set"),
-    //         "How?".to_string(),
-    //         "Please help!".to_string(),
-    //         Some(vec!["general".to_string()]),
-    //     );
-    //     self.add_question(question)
-    // }
 
-    pub async fn add_account(self, account: Account) -> Result<bool, Error> {
-        match sqlx::query("INSERT INTO accounts (email, password) VALUES ($1, $2)")
-            .bind(account.email)
-            .bind(account.password)
-            .execute(&self.connection)
-            .await
+    pub async fn get_answer(self, id: i32) -> Result<Answer, Error> {
+        match sqlx::query(&format!(
+            "SELECT {} from answers
+            JOIN questions ON questions.id = answers.corresponding_question
+            WHERE answers.id = $1 AND questions.deleted_at IS NULL",
+            ANSWER_COLUMNS
+        ))
+        .bind(id)
+        .map(|row: PgRow| answer_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
         {
-            Ok(_) => Ok(true),
+            Ok(Some(answer)) => Ok(answer),
+            Ok(None) => Err(Error::AnswerNotFound),
             Err(error) => {
-                tracing::event!(
-                    tracing::Level::ERROR,
-                    code = error
-                        .as_database_error()
-                        .unwrap()
-                        .code()
-                        .unwrap()
-                        .parse::<i32>()
-                        .unwrap(),
-                    db_message = error.as_database_error().unwrap().message(),
-                    constraint = error.as_database_error().unwrap().constraint().unwrap()
-                );
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
             }
         }
     }
 
-    pub async fn get_account(self, email: String) -> Result<Account, Error> {
-        match sqlx::query("SELECT * from account where email = $1")
-            .bind(email)
-            .map(|row: PgRow| Account {
-                id: Some(AccountId(row.get("id"))),
-                email: row.get("email"),
-                password: row.get("password"),
-            })
-            .fetch_one(&self.connection)
+    pub async fn update_answer(self, id: i32, content: String) -> Result<Answer, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+        let answer = save_answer_update(&mut tx, id, content).await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(answer)
+    }
+
+    /// Deletes the answer with its attachments and returns their storage keys, whose files
+    /// are up to the caller to delete
+    pub async fn delete_answer(self, id: i32) -> Result<Vec<String>, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let storage_keys =
+            match sqlx::query("DELETE from attachments WHERE answer_id = $1 RETURNING storage_key")
+                .bind(id)
+                .map(|row: PgRow| row.get::<String, _>("storage_key"))
+                .fetch_all(&mut *tx)
+                .await
+            {
+                Ok(storage_keys) => storage_keys,
+                Err(error) => {
+                    tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                    return Err(Error::DatabaseQueryError(error));
+                }
+            };
+
+        if let Err(error) = sqlx::query("DELETE from answers WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
             .await
         {
-            Ok(account) => Ok(account),
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(storage_keys)
+    }
+
+    /// Comments left on the question and on any of its answers, oldest first
+    pub async fn get_comments(self, question_id: i32) -> Result<Vec<Comment>, Error> {
+        match sqlx::query(
+            "SELECT comments.* from comments
+            LEFT JOIN answers ON answers.id = comments.answer_id
+            WHERE comments.question_id = $1 OR answers.corresponding_question = $1
+            ORDER BY comments.created_on, comments.id",
+        )
+        .bind(question_id)
+        .map(|row: PgRow| comment_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(comments) => Ok(comments),
             Err(error) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", error);
                 Err(Error::DatabaseQueryError(error))
@@ -250,18 +653,1583 @@ impl Store {
         }
     }
 
-    pub async fn is_question_owner(
-        &self,
-        question_id: i32,
-        account_id: &AccountId,
-    ) -> Result<bool, Error> {
-        match sqlx::query("SELECT * from questions where id = $1 and account_id = $2")
-            .bind(question_id)
-            .bind(account_id.0)
-            .fetch_optional(&self.connection)
-            .await
+    pub async fn add_comment(
+        self,
+        post: &Post,
+        content: String,
+        account_id: AccountId,
+    ) -> Result<Comment, Error> {
+        match sqlx::query(&format!(
+            "INSERT INTO comments (content, {}, account_id) VALUES ($1, $2, $3) RETURNING *",
+            post.column()
+        ))
+        .bind(content)
+        .bind(post.id())
+        .bind(account_id.0)
+        .map(|row: PgRow| comment_from_row(&row))
+        .fetch_one(&self.connection)
+        .await
         {
-            Ok(question) => Ok(question.is_some()),
+            Ok(comment) => Ok(comment),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn update_comment(self, id: i32, content: String) -> Result<Comment, Error> {
+        match sqlx::query(
+            "UPDATE comments SET content = $1, updated_on = NOW() WHERE id = $2 RETURNING *",
+        )
+        .bind(content)
+        .bind(id)
+        .map(|row: PgRow| comment_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(comment)) => Ok(comment),
+            Ok(None) => Err(Error::CommentNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn delete_comment(self, id: i32) -> Result<bool, Error> {
+        match sqlx::query("DELETE from comments WHERE id = $1")
+            .bind(id)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn add_suggested_edit(
+        self,
+        post: &Post,
+        title: Option<String>,
+        content: String,
+        tags: Option<Vec<String>>,
+        account_id: AccountId,
+    ) -> Result<SuggestedEdit, Error> {
+        match sqlx::query(&format!(
+            "INSERT INTO suggested_edits ({}, title, content, tags, account_id)
+            VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            post.column()
+        ))
+        .bind(post.id())
+        .bind(title)
+        .bind(content)
+        .bind(tags)
+        .bind(account_id.0)
+        .map(|row: PgRow| suggested_edit_from_row(&row))
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(edit) => Ok(edit),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_suggested_edit(self, id: i32) -> Result<SuggestedEdit, Error> {
+        match sqlx::query("SELECT * from suggested_edits WHERE id = $1")
+            .bind(id)
+            .map(|row: PgRow| suggested_edit_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(edit)) => Ok(edit),
+            Ok(None) => Err(Error::SuggestedEditNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Pending edits to posts of the given account, or to all posts when none is given
+    pub async fn get_pending_edits(
+        self,
+        account_id: Option<&AccountId>,
+    ) -> Result<Vec<SuggestedEdit>, Error> {
+        match sqlx::query(
+            "SELECT suggested_edits.* from suggested_edits
+            LEFT JOIN answers ON answers.id = suggested_edits.answer_id
+            JOIN questions
+                ON questions.id = COALESCE(suggested_edits.question_id, answers.corresponding_question)
+            WHERE suggested_edits.status = 'pending'
+                AND questions.deleted_at IS NULL
+                AND ($1::integer IS NULL OR COALESCE(answers.account_id, questions.account_id) = $1)
+            ORDER BY suggested_edits.created_on, suggested_edits.id",
+        )
+        .bind(account_id.map(|account_id| account_id.0))
+        .map(|row: PgRow| suggested_edit_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(edits) => Ok(edits),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Claims a pending edit and applies it like a normal update of the post in the same
+    /// transaction, so it is applied at most once, then records the suggester as a contributor
+    /// of the post. An `improvement` by the reviewer replaces what was suggested.
+    pub async fn approve_suggested_edit(
+        self,
+        id: i32,
+        reviewer_id: &AccountId,
+        improvement: Option<SuggestedEditUpdate>,
+    ) -> Result<SuggestedEdit, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let query = match improvement {
+            Some(improvement) => sqlx::query(
+                "UPDATE suggested_edits
+                SET status = 'approved', reviewer_id = $1, reviewed_on = NOW(),
+                    title = $3, content = $4, tags = $5
+                WHERE id = $2 AND status = 'pending' RETURNING *",
+            )
+            .bind(reviewer_id.0)
+            .bind(id)
+            .bind(improvement.title)
+            .bind(improvement.content)
+            .bind(improvement.tags),
+            None => sqlx::query(
+                "UPDATE suggested_edits
+                SET status = 'approved', reviewer_id = $1, reviewed_on = NOW()
+                WHERE id = $2 AND status = 'pending' RETURNING *",
+            )
+            .bind(reviewer_id.0)
+            .bind(id),
+        };
+
+        let edit = match query
+            .map(|row: PgRow| suggested_edit_from_row(&row))
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(edit)) => edit,
+            Ok(None) => return Err(Error::SuggestedEditAlreadyReviewed),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        let post = edit.post();
+        match &post {
+            Post::Question(question_id) => {
+                let question = NewQuestion {
+                    title: edit.title.clone().ok_or_else(|| {
+                        Error::InvalidParameter(String::from("edits to questions need a title"))
+                    })?,
+                    content: edit.content.clone(),
+                    tags: edit.tags.clone(),
+                };
+                save_question_update(&mut tx, question, question_id.0, &edit.account_id).await?;
+            }
+            Post::Answer(answer_id) => {
+                save_answer_update(&mut tx, answer_id.0, edit.content.clone()).await?;
+            }
+        }
+
+        if let Err(error) = sqlx::query(&format!(
+            "INSERT INTO contributors (account_id, {column}) VALUES ($1, $2)
+            ON CONFLICT ({column}, account_id) WHERE {column} IS NOT NULL DO NOTHING",
+            column = post.column()
+        ))
+        .bind(edit.account_id.0)
+        .bind(post.id())
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(edit)
+    }
+
+    pub async fn reject_suggested_edit(
+        self,
+        id: i32,
+        reviewer_id: &AccountId,
+    ) -> Result<SuggestedEdit, Error> {
+        match sqlx::query(
+            "UPDATE suggested_edits SET status = $1, reviewer_id = $2, reviewed_on = NOW()
+            WHERE id = $3 AND status = 'pending' RETURNING *",
+        )
+        .bind(EditStatus::Rejected.as_str())
+        .bind(reviewer_id.0)
+        .bind(id)
+        .map(|row: PgRow| suggested_edit_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(edit)) => Ok(edit),
+            Ok(None) => Err(Error::SuggestedEditAlreadyReviewed),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Accounts that had suggested edits to the post approved, in the order they were approved
+    pub async fn get_contributors(self, post: &Post) -> Result<Vec<AccountId>, Error> {
+        match sqlx::query(&format!(
+            "SELECT account_id from contributors WHERE {} = $1 ORDER BY created_on, id",
+            post.column()
+        ))
+        .bind(post.id())
+        .map(|row: PgRow| AccountId(row.get("account_id")))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(contributors) => Ok(contributors),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Offers a bounty on the question, debiting its amount from the account's points
+    pub async fn add_bounty(
+        self,
+        question_id: i32,
+        account_id: &AccountId,
+        new_bounty: NewBounty,
+    ) -> Result<Bounty, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let balance = match sqlx::query("SELECT points from accounts WHERE id = $1 FOR UPDATE")
+            .bind(account_id.0)
+            .map(|row: PgRow| row.get::<i32, _>("points"))
+            .fetch_one(&mut *tx)
+            .await
+        {
+            Ok(balance) => balance,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+        if balance < new_bounty.amount {
+            return Err(Error::InsufficientPoints);
+        }
+
+        let bounty = match sqlx::query(
+            "INSERT INTO bounties (question_id, account_id, amount, expires_on)
+            VALUES ($1, $2, $3, NOW() + make_interval(days => $4)) RETURNING *",
+        )
+        .bind(question_id)
+        .bind(account_id.0)
+        .bind(new_bounty.amount)
+        .bind(new_bounty.days)
+        .map(|row: PgRow| bounty_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(bounty) => bounty,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        add_ledger_entry(
+            &mut tx,
+            account_id,
+            -bounty.amount,
+            LedgerReason::BountyOffered,
+            &bounty.id,
+        )
+        .await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(bounty)
+    }
+
+    pub async fn get_open_bounty(self, question_id: i32) -> Result<Bounty, Error> {
+        match sqlx::query("SELECT * from bounties WHERE question_id = $1 AND status = 'open'")
+            .bind(question_id)
+            .map(|row: PgRow| bounty_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(bounty)) => Ok(bounty),
+            Ok(None) => Err(Error::BountyNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Pays an open bounty out to the owner of the answer.
+    /// Callers have to make sure the answer belongs to the bountied question.
+    pub async fn award_bounty(self, id: i32, answer_id: &AnswerId) -> Result<Bounty, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let bounty = close_bounty(&mut tx, id, BountyStatus::Awarded, Some(answer_id)).await?;
+
+        let answer_owner = match sqlx::query("SELECT account_id from answers WHERE id = $1")
+            .bind(answer_id.0)
+            .map(|row: PgRow| AccountId(row.get("account_id")))
+            .fetch_optional(&mut *tx)
+            .await
+        {
+            Ok(Some(account_id)) => account_id,
+            Ok(None) => return Err(Error::AnswerNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        add_ledger_entry(
+            &mut tx,
+            &answer_owner,
+            bounty.amount,
+            LedgerReason::BountyAwarded,
+            &bounty.id,
+        )
+        .await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(bounty)
+    }
+
+    /// Closes an open bounty that ran out: it goes to the highest scored answer with a positive
+    /// score that is not by the owner of the bounty, and expires when there is none
+    pub async fn expire_bounty(self, bounty: &Bounty) -> Result<Bounty, Error> {
+        let top_answer = match sqlx::query(
+            "SELECT id from answers
+            WHERE corresponding_question = $1 AND account_id <> $2 AND score > 0
+            ORDER BY score DESC, created_on, id LIMIT 1",
+        )
+        .bind(bounty.question_id.0)
+        .bind(bounty.account_id.0)
+        .map(|row: PgRow| AnswerId(row.get("id")))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(answer_id) => answer_id,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        if let Some(answer_id) = top_answer {
+            return self.award_bounty(bounty.id.0, &answer_id).await;
+        }
+
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+        let bounty = close_bounty(&mut tx, bounty.id.0, BountyStatus::Expired, None).await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(bounty)
+    }
+
+    pub async fn get_expired_bounties(self) -> Result<Vec<Bounty>, Error> {
+        match sqlx::query(
+            "SELECT * from bounties WHERE status = 'open' AND expires_on <= NOW() ORDER BY expires_on, id",
+        )
+        .map(|row: PgRow| bounty_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(bounties) => Ok(bounties),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_points(self, account_id: &AccountId) -> Result<Points, Error> {
+        let balance = match sqlx::query("SELECT points from accounts WHERE id = $1")
+            .bind(account_id.0)
+            .map(|row: PgRow| row.get::<i32, _>("points"))
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(balance) => balance,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        match sqlx::query("SELECT * from points_ledger WHERE account_id = $1 ORDER BY id DESC")
+            .bind(account_id.0)
+            .map(|row: PgRow| LedgerEntry {
+                amount: row.get("amount"),
+                reason: match row.get::<&str, _>("reason") {
+                    "bounty_offered" => LedgerReason::BountyOffered,
+                    "bounty_awarded" => LedgerReason::BountyAwarded,
+                    _ => LedgerReason::InitialGrant,
+                },
+                bounty_id: row.get::<Option<i32>, _>("bounty_id").map(BountyId),
+                created_on: row.get("created_on"),
+            })
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(entries) => Ok(Points { balance, entries }),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn add_draft(
+        self,
+        draft: DraftContent,
+        account_id: &AccountId,
+    ) -> Result<Draft, Error> {
+        match sqlx::query(
+            "INSERT INTO drafts (title, content, tags, account_id) VALUES ($1, $2, $3, $4) RETURNING *",
+        )
+        .bind(draft.title)
+        .bind(draft.content)
+        .bind(draft.tags)
+        .bind(account_id.0)
+        .map(|row: PgRow| draft_from_row(&row))
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(draft) => Ok(draft),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Drafts of the account, the most recently saved first
+    pub async fn get_drafts(self, account_id: &AccountId) -> Result<Vec<Draft>, Error> {
+        match sqlx::query(
+            "SELECT * from drafts WHERE account_id = $1 ORDER BY updated_on DESC, id DESC",
+        )
+        .bind(account_id.0)
+        .map(|row: PgRow| draft_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(drafts) => Ok(drafts),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Drafts of other accounts are treated as missing
+    pub async fn get_draft(self, id: i32, account_id: &AccountId) -> Result<Draft, Error> {
+        match sqlx::query("SELECT * from drafts WHERE id = $1 AND account_id = $2")
+            .bind(id)
+            .bind(account_id.0)
+            .map(|row: PgRow| draft_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(draft)) => Ok(draft),
+            Ok(None) => Err(Error::DraftNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn update_draft(
+        self,
+        id: i32,
+        draft: DraftContent,
+        account_id: &AccountId,
+    ) -> Result<Draft, Error> {
+        match sqlx::query(
+            "UPDATE drafts SET title = $1, content = $2, tags = $3, updated_on = NOW()
+            WHERE id = $4 AND account_id = $5 RETURNING *",
+        )
+        .bind(draft.title)
+        .bind(draft.content)
+        .bind(draft.tags)
+        .bind(id)
+        .bind(account_id.0)
+        .map(|row: PgRow| draft_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(draft)) => Ok(draft),
+            Ok(None) => Err(Error::DraftNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn delete_draft(self, id: i32, account_id: &AccountId) -> Result<bool, Error> {
+        match sqlx::query("DELETE from drafts WHERE id = $1 AND account_id = $2")
+            .bind(id)
+            .bind(account_id.0)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Turns the draft into a question and deletes it in one transaction. The draft row stays
+    /// locked while `prepare` checks it, so concurrent publishes wait and then find it gone,
+    /// and a failing `prepare` leaves the draft as it was.
+    pub async fn publish_draft<F, Fut>(
+        self,
+        id: i32,
+        account_id: &AccountId,
+        prepare: F,
+    ) -> Result<Question, Error>
+    where
+        F: FnOnce(Draft) -> Fut,
+        Fut: Future<Output = Result<NewQuestion, Error>>,
+    {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let draft =
+            match sqlx::query("SELECT * from drafts WHERE id = $1 AND account_id = $2 FOR UPDATE")
+                .bind(id)
+                .bind(account_id.0)
+                .map(|row: PgRow| draft_from_row(&row))
+                .fetch_optional(&mut *tx)
+                .await
+            {
+                Ok(Some(draft)) => draft,
+                Ok(None) => return Err(Error::DraftNotFound),
+                Err(error) => {
+                    tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                    return Err(Error::DatabaseQueryError(error));
+                }
+            };
+
+        let question = prepare(draft).await?;
+        let question = save_new_question(&mut tx, question, account_id).await?;
+
+        if let Err(error) = sqlx::query("DELETE from drafts WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(question)
+    }
+
+    /// Adds the attachment unless it would take the account's attachments over `quota` bytes.
+    /// The account row is locked, so parallel uploads cannot pass the check together.
+    pub async fn add_attachment(
+        self,
+        post: &Post,
+        account_id: &AccountId,
+        attachment: NewAttachment,
+        quota: i64,
+    ) -> Result<Attachment, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let usage = match sqlx::query(
+            "SELECT COALESCE(SUM(attachments.size), 0)::bigint AS usage
+            from (SELECT id from accounts WHERE id = $1 FOR UPDATE) account
+            LEFT JOIN attachments ON attachments.account_id = account.id",
+        )
+        .bind(account_id.0)
+        .map(|row: PgRow| row.get::<i64, _>("usage"))
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(usage) => usage,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+        if usage + attachment.size > quota {
+            return Err(Error::AttachmentQuotaExceeded);
+        }
+
+        let attachment = match sqlx::query(&format!(
+            "INSERT INTO attachments ({}, account_id, storage_key, file_name, content_type, size)
+            VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+            post.column()
+        ))
+        .bind(post.id())
+        .bind(account_id.0)
+        .bind(attachment.storage_key)
+        .bind(attachment.file_name)
+        .bind(attachment.content_type)
+        .bind(attachment.size)
+        .map(|row: PgRow| attachment_from_row(&row))
+        .fetch_one(&mut *tx)
+        .await
+        {
+            Ok(attachment) => attachment,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(attachment)
+    }
+
+    /// The attachment, unless its question or the question of its answer is in the trash
+    pub async fn get_attachment(self, id: i32) -> Result<Attachment, Error> {
+        match sqlx::query(
+            "SELECT attachments.* from attachments
+            LEFT JOIN answers ON answers.id = attachments.answer_id
+            JOIN questions
+                ON questions.id = COALESCE(attachments.question_id, answers.corresponding_question)
+            WHERE attachments.id = $1 AND questions.deleted_at IS NULL",
+        )
+        .bind(id)
+        .map(|row: PgRow| attachment_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(attachment)) => Ok(attachment),
+            Ok(None) => Err(Error::AttachmentNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Attachments of all given posts in one query, posts without any are missing from the map
+    pub async fn get_attachments(
+        self,
+        posts: &[Post],
+    ) -> Result<HashMap<Post, Vec<Attachment>>, Error> {
+        let (mut question_ids, mut answer_ids) = (Vec::new(), Vec::new());
+        for post in posts {
+            match post {
+                Post::Question(id) => question_ids.push(id.0),
+                Post::Answer(id) => answer_ids.push(id.0),
+            }
+        }
+
+        match sqlx::query(
+            "SELECT * from attachments WHERE question_id = ANY($1) OR answer_id = ANY($2)
+            ORDER BY created_on, id",
+        )
+        .bind(question_ids)
+        .bind(answer_ids)
+        .map(|row: PgRow| {
+            let post = match row.get::<Option<i32>, _>("question_id") {
+                Some(id) => Post::Question(QuestionId(id)),
+                None => Post::Answer(AnswerId(row.get("answer_id"))),
+            };
+            (post, attachment_from_row(&row))
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(rows) => {
+                let mut attachments: HashMap<Post, Vec<Attachment>> = HashMap::new();
+                for (post, attachment) in rows {
+                    attachments.entry(post).or_default().push(attachment);
+                }
+                Ok(attachments)
+            }
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Bytes of attachments the account has stored, to check it against its quota
+    pub async fn get_attachment_usage(self, account_id: &AccountId) -> Result<i64, Error> {
+        match sqlx::query(
+            "SELECT COALESCE(SUM(size), 0)::bigint AS usage from attachments WHERE account_id = $1",
+        )
+        .bind(account_id.0)
+        .map(|row: PgRow| row.get::<i64, _>("usage"))
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(usage) => Ok(usage),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn delete_attachment(self, id: i32) -> Result<bool, Error> {
+        match sqlx::query("DELETE from attachments WHERE id = $1")
+            .bind(id)
+            .execute(&self.connection)
+            .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    // fn add_question(mut self, question: Question) -> Self {
+    //     self.questions.insert(question.id.clone(), question);
+    //     self
+    // }
+
+    // fn init(self) -> Self {
+    //     let question = Question::new(
+    //         QuestionId::from_str("1").expect("Id not set"),
+    //         "How?".to_string(),
+    //         "Please help!".to_string(),
+    //         Some(vec!["general".to_string()]),
+    //     );
+    //     self.add_question(question)
+    // }
+
+    /// New accounts start unverified, with the first verification email counted as sent
+    pub async fn add_account(self, account: Account) -> Result<AccountId, Error> {
+        match sqlx::query(
+            "INSERT INTO accounts (email, password, verification_sent_on) VALUES ($1, $2, NOW())
+            RETURNING id",
+        )
+        .bind(account.email)
+        .bind(account.password)
+        .map(|row: PgRow| AccountId(row.get("id")))
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(account_id) => Ok(account_id),
+            Err(error) => {
+                tracing::event!(
+                    tracing::Level::ERROR,
+                    code = error
+                        .as_database_error()
+                        .unwrap()
+                        .code()
+                        .unwrap()
+                        .parse::<i32>()
+                        .unwrap(),
+                    db_message = error.as_database_error().unwrap().message(),
+                    constraint = error.as_database_error().unwrap().constraint().unwrap()
+                );
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_account(self, email: String) -> Result<Account, Error> {
+        match sqlx::query("SELECT * from accounts where email = $1")
+            .bind(email)
+            .map(|row: PgRow| Account {
+                id: Some(AccountId(row.get("id"))),
+                email: row.get("email"),
+                password: row.get("password"),
+                role: Role::from_column(row.get("role")),
+            })
+            .fetch_one(&self.connection)
+            .await
+        {
+            Ok(account) => Ok(account),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn get_accounts(self) -> Result<Vec<AccountSummary>, Error> {
+        match sqlx::query("SELECT * from accounts ORDER BY id")
+            .map(|row: PgRow| account_summary_from_row(&row))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(accounts) => Ok(accounts),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn set_account_role(self, id: i32, role: Role) -> Result<AccountSummary, Error> {
+        match sqlx::query("UPDATE accounts SET role = $1 WHERE id = $2 RETURNING *")
+            .bind(role.as_str())
+            .bind(id)
+            .map(|row: PgRow| account_summary_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(account)) => Ok(account),
+            Ok(None) => Err(Error::UserNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn is_email_verified(&self, account_id: &AccountId) -> Result<bool, Error> {
+        match sqlx::query("SELECT verified_on IS NOT NULL AS verified from accounts WHERE id = $1")
+            .bind(account_id.0)
+            .map(|row: PgRow| row.get::<bool, _>("verified"))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(verified)) => Ok(verified),
+            Ok(None) => Err(Error::UserNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Marks the account as verified, as long as it still has the email the link was sent to
+    pub async fn verify_email(self, account_id: &AccountId, email: &str) -> Result<bool, Error> {
+        match sqlx::query(
+            "UPDATE accounts SET verified_on = COALESCE(verified_on, NOW())
+            WHERE id = $1 AND email = $2 RETURNING id",
+        )
+        .bind(account_id.0)
+        .bind(email)
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(_)) => Ok(true),
+            Ok(None) => Err(Error::InvalidVerificationToken),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Records that another verification email goes out and returns the address to send it to.
+    /// Fails for verified accounts and when the last one went out less than `minutes` ago.
+    pub async fn claim_verification_resend(
+        self,
+        account_id: &AccountId,
+        minutes: i32,
+    ) -> Result<String, Error> {
+        match sqlx::query(
+            "UPDATE accounts SET verification_sent_on = NOW()
+            WHERE id = $1 AND verified_on IS NULL
+            AND (verification_sent_on IS NULL
+                OR verification_sent_on < NOW() - make_interval(mins => $2))
+            RETURNING email",
+        )
+        .bind(account_id.0)
+        .bind(minutes)
+        .map(|row: PgRow| row.get::<String, _>("email"))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(email)) => Ok(email),
+            Ok(None) => {
+                if self.is_email_verified(account_id).await? {
+                    Err(Error::EmailAlreadyVerified)
+                } else {
+                    Err(Error::TooManyRequests)
+                }
+            }
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Id of the account with this email, `None` if there is none
+    pub async fn find_account_id(self, email: &str) -> Result<Option<AccountId>, Error> {
+        match sqlx::query("SELECT id from accounts WHERE email = $1")
+            .bind(email)
+            .map(|row: PgRow| AccountId(row.get("id")))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(account_id) => Ok(account_id),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn add_password_reset(
+        self,
+        account_id: &AccountId,
+        token_hash: &str,
+        minutes: i32,
+    ) -> Result<bool, Error> {
+        match sqlx::query(
+            "INSERT INTO password_resets (account_id, token_hash, expires_on)
+            VALUES ($1, $2, NOW() + make_interval(mins => $3))",
+        )
+        .bind(account_id.0)
+        .bind(token_hash)
+        .bind(minutes)
+        .execute(&self.connection)
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Uses up the reset token and stores the new password hash.
+    /// Any other outstanding tokens and every session of the account stop working as well,
+    /// returns the jti of the access tokens that got revoked with the sessions.
+    pub async fn reset_password(
+        self,
+        token_hash: &str,
+        password: String,
+        access_minutes: i32,
+    ) -> Result<Vec<String>, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let account_id = match sqlx::query(
+            "UPDATE password_resets SET used_on = NOW()
+            WHERE token_hash = $1 AND used_on IS NULL AND expires_on > NOW()
+            RETURNING account_id",
+        )
+        .bind(token_hash)
+        .map(|row: PgRow| AccountId(row.get("account_id")))
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(Some(account_id)) => account_id,
+            Ok(None) => return Err(Error::InvalidResetToken),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        if let Err(error) = sqlx::query("UPDATE accounts SET password = $1 WHERE id = $2")
+            .bind(password)
+            .bind(account_id.0)
+            .execute(&mut *tx)
+            .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        if let Err(error) = sqlx::query(
+            "UPDATE password_resets SET used_on = NOW() WHERE account_id = $1 AND used_on IS NULL",
+        )
+        .bind(account_id.0)
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        let revoked = match sqlx::query(
+            "UPDATE sessions SET revoked_on = NOW()
+            WHERE account_id = $1 AND revoked_on IS NULL AND expires_on > NOW()
+            RETURNING access_jti",
+        )
+        .bind(account_id.0)
+        .map(|row: PgRow| row.get::<String, _>("access_jti"))
+        .fetch_all(&mut *tx)
+        .await
+        {
+            Ok(revoked) => revoked,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        for jti in &revoked {
+            revoke_access_token(&mut tx, jti, access_minutes).await?;
+        }
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(revoked)
+    }
+
+    pub async fn add_session(
+        self,
+        account_id: &AccountId,
+        refresh_token_hash: &str,
+        access_jti: &str,
+        user_agent: Option<String>,
+        days: i32,
+    ) -> Result<SessionId, Error> {
+        match sqlx::query(
+            "INSERT INTO sessions (account_id, refresh_token_hash, access_jti, user_agent, expires_on)
+            VALUES ($1, $2, $3, $4, NOW() + make_interval(days => $5)) RETURNING id",
+        )
+        .bind(account_id.0)
+        .bind(refresh_token_hash)
+        .bind(access_jti)
+        .bind(user_agent)
+        .bind(days)
+        .map(|row: PgRow| SessionId(row.get("id")))
+        .fetch_one(&self.connection)
+        .await
+        {
+            Ok(id) => Ok(id),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Swaps the refresh token of a live session for a new one and revokes its last access token.
+    /// Returns the account with its current role and the revoked jti.
+    pub async fn refresh_session(
+        self,
+        refresh_token_hash: &str,
+        new_refresh_token_hash: &str,
+        new_access_jti: &str,
+        access_minutes: i32,
+    ) -> Result<(AccountId, Role, String), Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let (id, account_id, role, access_jti) = match sqlx::query(
+            "SELECT sessions.id, sessions.account_id, sessions.access_jti, accounts.role
+            FROM sessions JOIN accounts ON accounts.id = sessions.account_id
+            WHERE sessions.refresh_token_hash = $1
+            AND sessions.revoked_on IS NULL AND sessions.expires_on > NOW()
+            FOR UPDATE OF sessions",
+        )
+        .bind(refresh_token_hash)
+        .map(|row: PgRow| {
+            (
+                row.get::<i32, _>("id"),
+                AccountId(row.get("account_id")),
+                Role::from_column(row.get("role")),
+                row.get::<String, _>("access_jti"),
+            )
+        })
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(Some(session)) => session,
+            Ok(None) => return Err(Error::Unauthorized),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        revoke_access_token(&mut tx, &access_jti, access_minutes).await?;
+
+        if let Err(error) = sqlx::query(
+            "UPDATE sessions SET refresh_token_hash = $1, access_jti = $2, last_used_on = NOW()
+            WHERE id = $3",
+        )
+        .bind(new_refresh_token_hash)
+        .bind(new_access_jti)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok((account_id, role, access_jti))
+    }
+
+    /// Live sessions of the account, `current` marks the one `access_jti` belongs to
+    pub async fn get_sessions(
+        self,
+        account_id: &AccountId,
+        access_jti: &str,
+    ) -> Result<Vec<ActiveSession>, Error> {
+        match sqlx::query(
+            "SELECT *, access_jti = $2 AS current from sessions
+            WHERE account_id = $1 AND revoked_on IS NULL AND expires_on > NOW()
+            ORDER BY last_used_on DESC",
+        )
+        .bind(account_id.0)
+        .bind(access_jti)
+        .map(|row: PgRow| ActiveSession {
+            id: SessionId(row.get("id")),
+            user_agent: row.get("user_agent"),
+            created_on: row.get("created_on"),
+            last_used_on: row.get("last_used_on"),
+            expires_on: row.get("expires_on"),
+            current: row.get("current"),
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(sessions) => Ok(sessions),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Ends a session of the account, found by its id or the jti of its access token.
+    /// Returns the jti that got revoked with it.
+    pub async fn revoke_session(
+        self,
+        account_id: &AccountId,
+        id: Option<&SessionId>,
+        access_jti: Option<&str>,
+        access_minutes: i32,
+    ) -> Result<String, Error> {
+        let mut tx = self
+            .connection
+            .begin()
+            .await
+            .map_err(Error::DatabaseQueryError)?;
+
+        let access_jti = match sqlx::query(
+            "UPDATE sessions SET revoked_on = NOW()
+            WHERE account_id = $1 AND (id = $2 OR access_jti = $3) AND revoked_on IS NULL
+            RETURNING access_jti",
+        )
+        .bind(account_id.0)
+        .bind(id.map(|id| id.0))
+        .bind(access_jti)
+        .map(|row: PgRow| row.get::<String, _>("access_jti"))
+        .fetch_optional(&mut *tx)
+        .await
+        {
+            Ok(Some(access_jti)) => access_jti,
+            Ok(None) => return Err(Error::SessionNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        revoke_access_token(&mut tx, &access_jti, access_minutes).await?;
+        tx.commit().await.map_err(Error::DatabaseQueryError)?;
+
+        Ok(access_jti)
+    }
+
+    /// jti of all revoked access tokens that have not expired yet
+    pub async fn get_revoked_tokens(self) -> Result<Vec<String>, Error> {
+        match sqlx::query("SELECT jti from revoked_tokens WHERE expires_on > NOW()")
+            .map(|row: PgRow| row.get::<String, _>("jti"))
+            .fetch_all(&self.connection)
+            .await
+        {
+            Ok(jtis) => Ok(jtis),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Removes expired sessions and revoked tokens that would have expired anyway
+    pub async fn purge_expired_sessions(self) -> Result<u64, Error> {
+        let mut purged = 0;
+        for query in [
+            "DELETE FROM sessions WHERE expires_on < NOW()",
+            "DELETE FROM revoked_tokens WHERE expires_on < NOW()",
+        ] {
+            match sqlx::query(query).execute(&self.connection).await {
+                Ok(result) => purged += result.rows_affected(),
+                Err(error) => {
+                    tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                    return Err(Error::DatabaseQueryError(error));
+                }
+            }
+        }
+        Ok(purged)
+    }
+
+    pub async fn get_profile(self, id: i32) -> Result<Profile, Error> {
+        match sqlx::query("SELECT * from accounts WHERE id = $1")
+            .bind(id)
+            .map(|row: PgRow| profile_from_row(&row))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(profile)) => Ok(profile),
+            Ok(None) => Err(Error::UserNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn update_profile(self, id: i32, profile: ProfileUpdate) -> Result<Profile, Error> {
+        match sqlx::query(
+            "UPDATE accounts SET display_name = $1, bio = $2, avatar_url = $3 WHERE id = $4 RETURNING *",
+        )
+        .bind(profile.display_name)
+        .bind(profile.bio)
+        .bind(profile.avatar_url)
+        .bind(id)
+        .map(|row: PgRow| profile_from_row(&row))
+        .fetch_optional(&self.connection)
+        .await
+        {
+            Ok(Some(profile)) => Ok(profile),
+            Ok(None) => Err(Error::UserNotFound),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Latest questions asked by the account, leaving out the ones in its trash
+    pub async fn get_account_questions(
+        self,
+        account_id: i32,
+        limit: i32,
+    ) -> Result<Vec<Question>, Error> {
+        match sqlx::query(
+            "SELECT * from questions WHERE account_id = $1 AND deleted_at IS NULL
+            ORDER BY created_on DESC, id DESC LIMIT $2",
+        )
+        .bind(account_id)
+        .bind(limit)
+        .map(|row: PgRow| question_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(questions) => Ok(questions),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Latest answers given by the account to questions that are not deleted
+    pub async fn get_account_answers(
+        self,
+        account_id: i32,
+        limit: i32,
+    ) -> Result<Vec<Answer>, Error> {
+        match sqlx::query(&format!(
+            "SELECT {} from answers
+            JOIN questions ON questions.id = answers.corresponding_question
+            WHERE answers.account_id = $1 AND questions.deleted_at IS NULL
+            ORDER BY answers.created_on DESC, answers.id DESC LIMIT $2",
+            ANSWER_COLUMNS
+        ))
+        .bind(account_id)
+        .bind(limit)
+        .map(|row: PgRow| answer_from_row(&row))
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(answers) => Ok(answers),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn is_question_owner(
+        &self,
+        question_id: i32,
+        account_id: &AccountId,
+    ) -> Result<bool, Error> {
+        match sqlx::query("SELECT * from questions where id = $1 and account_id = $2")
+            .bind(question_id)
+            .bind(account_id.0)
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(question) => Ok(question.is_some()),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError(e))
+            }
+        }
+    }
+
+    /// Casts a vote, or changes the vote the account already cast on the post
+    pub async fn cast_vote(
+        self,
+        account_id: &AccountId,
+        post: &Post,
+        value: i16,
+    ) -> Result<bool, Error> {
+        match sqlx::query(&format!(
+            "INSERT INTO votes (account_id, {column}, value) VALUES ($1, $2, $3)
+            ON CONFLICT (account_id, {column}) WHERE {column} IS NOT NULL
+            DO UPDATE SET value = EXCLUDED.value",
+            column = post.column()
+        ))
+        .bind(account_id.0)
+        .bind(post.id())
+        .bind(value)
+        .execute(&self.connection)
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn retract_vote(self, account_id: &AccountId, post: &Post) -> Result<bool, Error> {
+        match sqlx::query(&format!(
+            "DELETE from votes WHERE account_id = $1 AND {} = $2",
+            post.column()
+        ))
+        .bind(account_id.0)
+        .bind(post.id())
+        .execute(&self.connection)
+        .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Adds the reaction to the post, returns false if the account already reacted that way
+    pub async fn add_reaction(
+        self,
+        account_id: &AccountId,
+        post: &Post,
+        reaction: Reaction,
+    ) -> Result<bool, Error> {
+        match sqlx::query(&format!(
+            "INSERT INTO reactions (account_id, {column}, reaction) VALUES ($1, $2, $3)
+            ON CONFLICT ({column}, account_id, reaction) WHERE {column} IS NOT NULL DO NOTHING",
+            column = post.column()
+        ))
+        .bind(account_id.0)
+        .bind(post.id())
+        .bind(reaction.as_str())
+        .execute(&self.connection)
+        .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    pub async fn remove_reaction(
+        self,
+        account_id: &AccountId,
+        post: &Post,
+        reaction: Reaction,
+    ) -> Result<bool, Error> {
+        match sqlx::query(&format!(
+            "DELETE from reactions WHERE account_id = $1 AND {} = $2 AND reaction = $3",
+            post.column()
+        ))
+        .bind(account_id.0)
+        .bind(post.id())
+        .bind(reaction.as_str())
+        .execute(&self.connection)
+        .await
+        {
+            Ok(result) => Ok(result.rows_affected() > 0),
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                Err(Error::DatabaseQueryError(error))
+            }
+        }
+    }
+
+    /// Reaction counts of all given posts in one query. Posts without reactions are missing
+    /// from the map, `reacted` is only ever set when an account is given.
+    pub async fn get_reactions(
+        self,
+        posts: &[Post],
+        account_id: Option<&AccountId>,
+    ) -> Result<HashMap<Post, Vec<ReactionCount>>, Error> {
+        let (mut question_ids, mut answer_ids) = (Vec::new(), Vec::new());
+        for post in posts {
+            match post {
+                Post::Question(id) => question_ids.push(id.0),
+                Post::Answer(id) => answer_ids.push(id.0),
+            }
+        }
+
+        let rows = match sqlx::query(
+            "SELECT question_id, answer_id, reaction, COUNT(*) AS count,
+                COALESCE(BOOL_OR(account_id = $3), false) AS reacted
+            from reactions
+            WHERE question_id = ANY($1) OR answer_id = ANY($2)
+            GROUP BY question_id, answer_id, reaction",
+        )
+        .bind(question_ids)
+        .bind(answer_ids)
+        .bind(account_id.map(|account_id| account_id.0))
+        .map(|row: PgRow| {
+            let post = match row.get::<Option<i32>, _>("question_id") {
+                Some(id) => Post::Question(QuestionId(id)),
+                None => Post::Answer(AnswerId(row.get("answer_id"))),
+            };
+            let reaction = row.get::<&str, _>("reaction").parse::<Reaction>();
+            (post, reaction, row.get("count"), row.get("reacted"))
+        })
+        .fetch_all(&self.connection)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(error) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", error);
+                return Err(Error::DatabaseQueryError(error));
+            }
+        };
+
+        let mut reactions: HashMap<Post, Vec<ReactionCount>> = HashMap::new();
+        for (post, reaction, count, reacted) in rows {
+            // The column only allows known reactions
+            if let Ok(reaction) = reaction {
+                reactions.entry(post).or_default().push(ReactionCount {
+                    reaction,
+                    count,
+                    reacted,
+                });
+            }
+        }
+        for counts in reactions.values_mut() {
+            counts.sort_by_key(|count| count.reaction);
+        }
+
+        Ok(reactions)
+    }
+
+    pub async fn is_answer_owner(
+        &self,
+        answer_id: i32,
+        account_id: &AccountId,
+    ) -> Result<bool, Error> {
+        match sqlx::query("SELECT * from answers where id = $1 and account_id = $2")
+            .bind(answer_id)
+            .bind(account_id.0)
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(answer) => Ok(answer.is_some()),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError(e))
+            }
+        }
+    }
+
+    pub async fn is_comment_owner(
+        &self,
+        comment_id: i32,
+        account_id: &AccountId,
+    ) -> Result<bool, Error> {
+        match sqlx::query("SELECT * from comments where id = $1 and account_id = $2")
+            .bind(comment_id)
+            .bind(account_id.0)
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(comment) => Ok(comment.is_some()),
+            Err(e) => {
+                tracing::event!(tracing::Level::ERROR, "{:?}", e);
+                Err(Error::DatabaseQueryError(e))
+            }
+        }
+    }
+
+    /// Whether the question still accepts answers and edits, see `QuestionStatus`
+    pub async fn is_question_open(&self, question_id: i32) -> Result<bool, Error> {
+        match sqlx::query("SELECT status from questions where id = $1 and deleted_at IS NULL")
+            .bind(question_id)
+            .map(|row: PgRow| row.get::<String, _>("status"))
+            .fetch_optional(&self.connection)
+            .await
+        {
+            Ok(Some(status)) => Ok(status == "open"),
+            Ok(None) => Err(Error::QuestionNotFound),
             Err(e) => {
                 tracing::event!(tracing::Level::ERROR, "{:?}", e);
                 Err(Error::DatabaseQueryError(e))
@@ -274,3 +2242,359 @@ impl Store {
     //     serde_json::from_str(file).expect("can't read questions.json")
     // }
 }
+
+/// Adds the `WHERE` conditions of a question listing to a query that already has a `WHERE` clause
+fn push_question_filter(query: &mut QueryBuilder<'_, Postgres>, filter: &QuestionFilter) {
+    if let Some(tag_filter) = &filter.tags {
+        match tag_filter.mode {
+            TagMatch::Any => query.push(" AND tags && "),
+            TagMatch::All => query.push(" AND tags @> "),
+        };
+        query.push_bind(tag_filter.tags.clone());
+    }
+
+    if let Some(answers) = filter.answers {
+        query.push(match answers {
+            AnswerFilter::Answered => " AND answer_count > 0",
+            AnswerFilter::Unanswered => " AND answer_count = 0",
+            AnswerFilter::Accepted => " AND accepted_answer_id IS NOT NULL",
+        });
+    }
+
+    if filter.bounty {
+        query.push(
+            " AND EXISTS (SELECT 1 from bounties WHERE bounties.question_id = questions.id AND bounties.status = 'open')",
+        );
+    }
+}
+
+/// Credits (positive `amount`) or debits points, the balance follows through a trigger
+async fn add_ledger_entry(
+    tx: &mut Transaction<'_, Postgres>,
+    account_id: &AccountId,
+    amount: i32,
+    reason: LedgerReason,
+    bounty_id: &BountyId,
+) -> Result<(), Error> {
+    match sqlx::query(
+        "INSERT INTO points_ledger (account_id, amount, reason, bounty_id) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(account_id.0)
+    .bind(amount)
+    .bind(reason.as_str())
+    .bind(bounty_id.0)
+    .execute(&mut **tx)
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            Err(Error::DatabaseQueryError(error))
+        }
+    }
+}
+
+/// Puts the jti on the revocation list for as long as an access token can live
+async fn revoke_access_token(
+    tx: &mut Transaction<'_, Postgres>,
+    jti: &str,
+    access_minutes: i32,
+) -> Result<(), Error> {
+    match sqlx::query(
+        "INSERT INTO revoked_tokens (jti, expires_on)
+        VALUES ($1, NOW() + make_interval(mins => $2)) ON CONFLICT (jti) DO NOTHING",
+    )
+    .bind(jti)
+    .bind(access_minutes)
+    .execute(&mut **tx)
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            Err(Error::DatabaseQueryError(error))
+        }
+    }
+}
+
+async fn close_bounty(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    status: BountyStatus,
+    answer_id: Option<&AnswerId>,
+) -> Result<Bounty, Error> {
+    match sqlx::query(
+        "UPDATE bounties SET status = $1, awarded_answer_id = $2, closed_on = NOW()
+        WHERE id = $3 AND status = 'open' RETURNING *",
+    )
+    .bind(status.as_str())
+    .bind(answer_id.map(|answer_id| answer_id.0))
+    .bind(id)
+    .map(|row: PgRow| bounty_from_row(&row))
+    .fetch_optional(&mut **tx)
+    .await
+    {
+        Ok(Some(bounty)) => Ok(bounty),
+        Ok(None) => Err(Error::BountyNotFound),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            Err(Error::DatabaseQueryError(error))
+        }
+    }
+}
+
+/// Inserts the question and records it as its first revision
+async fn save_new_question(
+    tx: &mut Transaction<'_, Postgres>,
+    new_question: NewQuestion,
+    account_id: &AccountId,
+) -> Result<Question, Error> {
+    let question = match sqlx::query("INSERT INTO questions (title, content, tags, account_id) VALUES ($1, $2, $3, $4) RETURNING *")
+        .bind(new_question.title)
+        .bind(new_question.content)
+        .bind(new_question.tags)
+        .bind(account_id.0)
+        .map(|row: PgRow| question_from_row(&row))
+        .fetch_one(&mut **tx)
+        .await
+    {
+        Ok(question) => question,
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+    };
+
+    add_revision(tx, &question, account_id).await?;
+
+    Ok(question)
+}
+
+/// Overwrites the question and records the new state as a revision made by `account_id`
+async fn save_question_update(
+    tx: &mut Transaction<'_, Postgres>,
+    question: NewQuestion,
+    id: i32,
+    account_id: &AccountId,
+) -> Result<Question, Error> {
+    let question = match sqlx::query("UPDATE questions SET title = $1, content = $2, tags = $3 WHERE id = $4 AND deleted_at IS NULL RETURNING *")
+    .bind(question.title)
+    .bind(question.content)
+    .bind(question.tags)
+    .bind(id)
+    .map(|row: PgRow| question_from_row(&row))
+    .fetch_optional(&mut **tx)
+    .await {
+        Ok(Some(question)) => question,
+        Ok(None) => return Err(Error::QuestionNotFound),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            return Err(Error::DatabaseQueryError(error));
+        }
+    };
+
+    add_revision(tx, &question, account_id).await?;
+
+    Ok(question)
+}
+
+async fn save_answer_update(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i32,
+    content: String,
+) -> Result<Answer, Error> {
+    match sqlx::query(&format!(
+        "UPDATE answers SET content = $1 WHERE id = $2 RETURNING {}",
+        ANSWER_COLUMNS
+    ))
+    .bind(content)
+    .bind(id)
+    .map(|row: PgRow| answer_from_row(&row))
+    .fetch_optional(&mut **tx)
+    .await
+    {
+        Ok(Some(answer)) => Ok(answer),
+        Ok(None) => Err(Error::AnswerNotFound),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            Err(Error::DatabaseQueryError(error))
+        }
+    }
+}
+
+/// Records the current state of a question in its revision history
+async fn add_revision(
+    tx: &mut Transaction<'_, Postgres>,
+    question: &Question,
+    account_id: &AccountId,
+) -> Result<(), Error> {
+    match sqlx::query(
+        "INSERT INTO question_revisions (question_id, title, content, tags, account_id) VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(question.id.0)
+    .bind(question.title.clone())
+    .bind(question.content.clone())
+    .bind(question.tags.clone())
+    .bind(account_id.0)
+    .execute(&mut **tx)
+    .await
+    {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            tracing::event!(tracing::Level::ERROR, "{:?}", error);
+            Err(Error::DatabaseQueryError(error))
+        }
+    }
+}
+
+fn question_from_row(row: &PgRow) -> Question {
+    let status = match row.get::<&str, _>("status") {
+        "closed" => QuestionStatus::Closed {
+            reason: row
+                .get::<Option<String>, _>("close_reason")
+                .unwrap_or_default(),
+        },
+        "duplicate" => match row.get::<Option<i32>, _>("duplicate_of") {
+            Some(id) => QuestionStatus::Duplicate { of: QuestionId(id) },
+            // The original question has been purged since
+            None => QuestionStatus::Closed {
+                reason: row
+                    .get::<Option<String>, _>("close_reason")
+                    .unwrap_or_default(),
+            },
+        },
+        "locked" => QuestionStatus::Locked,
+        _ => QuestionStatus::Open,
+    };
+
+    Question {
+        id: QuestionId(row.get("id")),
+        title: row.get("title"),
+        content: row.get("content"),
+        tags: row.get("tags"),
+        status,
+        accepted_answer_id: row
+            .get::<Option<i32>, _>("accepted_answer_id")
+            .map(AnswerId),
+        score: row.get("score"),
+        reactions: Vec::new(),
+        attachments: Vec::new(),
+    }
+}
+
+/// Columns every query returning answers selects, so `answer_from_row` can map them
+const ANSWER_COLUMNS: &str = "answers.*, EXISTS (
+    SELECT 1 from questions WHERE questions.accepted_answer_id = answers.id
+) AS is_accepted";
+
+fn answer_from_row(row: &PgRow) -> Answer {
+    Answer {
+        id: AnswerId(row.get("id")),
+        content: row.get("content"),
+        question_id: QuestionId(row.get("corresponding_question")),
+        is_accepted: row.get("is_accepted"),
+        score: row.get("score"),
+        reactions: Vec::new(),
+        attachments: Vec::new(),
+    }
+}
+
+fn comment_from_row(row: &PgRow) -> Comment {
+    Comment {
+        id: CommentId(row.get("id")),
+        content: row.get("content"),
+        question_id: row.get::<Option<i32>, _>("question_id").map(QuestionId),
+        answer_id: row.get::<Option<i32>, _>("answer_id").map(AnswerId),
+        account_id: AccountId(row.get("account_id")),
+        created_on: row.get("created_on"),
+        updated_on: row.get("updated_on"),
+    }
+}
+
+fn suggested_edit_from_row(row: &PgRow) -> SuggestedEdit {
+    let status = match row.get::<&str, _>("status") {
+        "approved" => EditStatus::Approved,
+        "rejected" => EditStatus::Rejected,
+        _ => EditStatus::Pending,
+    };
+
+    SuggestedEdit {
+        id: SuggestedEditId(row.get("id")),
+        question_id: row.get::<Option<i32>, _>("question_id").map(QuestionId),
+        answer_id: row.get::<Option<i32>, _>("answer_id").map(AnswerId),
+        account_id: AccountId(row.get("account_id")),
+        title: row.get("title"),
+        content: row.get("content"),
+        tags: row.get("tags"),
+        status,
+        reviewer_id: row.get::<Option<i32>, _>("reviewer_id").map(AccountId),
+        created_on: row.get("created_on"),
+        reviewed_on: row.get("reviewed_on"),
+    }
+}
+
+fn bounty_from_row(row: &PgRow) -> Bounty {
+    let status = match row.get::<&str, _>("status") {
+        "awarded" => BountyStatus::Awarded,
+        "expired" => BountyStatus::Expired,
+        _ => BountyStatus::Open,
+    };
+
+    Bounty {
+        id: BountyId(row.get("id")),
+        question_id: QuestionId(row.get("question_id")),
+        account_id: AccountId(row.get("account_id")),
+        amount: row.get("amount"),
+        status,
+        awarded_answer_id: row.get::<Option<i32>, _>("awarded_answer_id").map(AnswerId),
+        created_on: row.get("created_on"),
+        expires_on: row.get("expires_on"),
+        closed_on: row.get("closed_on"),
+    }
+}
+
+fn draft_from_row(row: &PgRow) -> Draft {
+    Draft {
+        id: DraftId(row.get("id")),
+        title: row.get("title"),
+        content: row.get("content"),
+        tags: row.get("tags"),
+        created_on: row.get("created_on"),
+        updated_on: row.get("updated_on"),
+    }
+}
+
+fn attachment_from_row(row: &PgRow) -> Attachment {
+    let id = row.get("id");
+
+    Attachment {
+        id: AttachmentId(id),
+        file_name: row.get("file_name"),
+        content_type: row.get("content_type"),
+        size: row.get("size"),
+        url: format!("/attachments/{}", id),
+        account_id: AccountId(row.get("account_id")),
+        created_on: row.get("created_on"),
+        storage_key: row.get("storage_key"),
+    }
+}
+
+fn profile_from_row(row: &PgRow) -> Profile {
+    Profile {
+        id: AccountId(row.get("id")),
+        display_name: row.get("display_name"),
+        bio: row.get("bio"),
+        avatar_url: row.get("avatar_url"),
+        joined_on: row.get("created_on"),
+    }
+}
+
+fn account_summary_from_row(row: &PgRow) -> AccountSummary {
+    AccountSummary {
+        id: AccountId(row.get("id")),
+        email: row.get("email"),
+        role: Role::from_column(row.get("role")),
+        verified_on: row.get("verified_on"),
+        joined_on: row.get("created_on"),
+    }
+}
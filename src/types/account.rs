@@ -1,18 +1,94 @@
 use chrono::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use handle_errors::Error;
+
+/// Minutes an account has to wait before another verification email is sent
+pub const VERIFICATION_RESEND_MINUTES: i32 = 5;
+/// How long the link in a verification email stays valid
+pub const VERIFICATION_TOKEN_HOURS: i64 = 24;
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Session {
     pub exp: DateTime<Utc>,
     pub account_id: AccountId,
+    /// Tokens issued before roles existed have no role claim and count as `Role::User`
+    #[serde(default)]
+    pub role: Role,
+    /// Unique id of the access token, checked against the revocation list
+    pub jti: String,
     // pub nbf: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Session {
+    /// Moderators (and admins) may edit and delete posts of other accounts
+    pub fn is_moderator(&self) -> bool {
+        self.role >= Role::Moderator
+    }
+}
+
+/// What an account is allowed to do, every role includes the ones before it
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    User,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    /// Value of the `role` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Moderator => "moderator",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn from_column(role: &str) -> Self {
+        match role {
+            "admin" => Role::Admin,
+            "moderator" => Role::Moderator,
+            _ => Role::User,
+        }
+    }
+}
+
+/// Credentials of an account, only ever read from requests and never sent back
+/// since `password` holds the hash once stored
+#[derive(Debug, Clone, Deserialize)]
 pub struct Account {
     pub id: Option<AccountId>,
     pub email: String,
     pub password: String,
+    /// Never taken from requests, so nobody can register as an admin
+    #[serde(skip)]
+    pub role: Role,
+}
+
+impl Account {
+    /// Only a sanity check, the verification email proves the address exists
+    pub fn validate_email(&self) -> Result<(), Error> {
+        let valid = match self.email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !self.email.chars().any(char::is_whitespace)
+            }
+            None => false,
+        };
+        if !valid || self.email.len() > 254 {
+            return Err(Error::InvalidParameter(String::from(
+                "email must be a valid email address",
+            )));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -23,3 +99,90 @@ pub struct NewAccount {
     pub email: String,
     pub password: String,
 }
+
+/// An account as admins see it when managing accounts
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountSummary {
+    pub id: AccountId,
+    pub email: String,
+    pub role: Role,
+    pub verified_on: Option<NaiveDateTime>,
+    pub joined_on: NaiveDateTime,
+}
+
+/// Claims of the token in verification emails
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EmailVerification {
+    pub account_id: AccountId,
+    pub email: String,
+}
+
+/// Query of the link in verification emails
+#[derive(Deserialize, Debug, Clone)]
+pub struct VerificationQuery {
+    pub token: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RoleUpdate {
+    pub role: Role,
+}
+
+#[cfg(test)]
+mod account_tests {
+    use super::{Account, Role, Session};
+
+    fn account(email: &str) -> Account {
+        Account {
+            id: None,
+            email: email.to_string(),
+            password: "pw".to_string(),
+            role: Role::User,
+        }
+    }
+
+    #[test]
+    fn roles_are_ordered() {
+        assert!(Role::Admin > Role::Moderator);
+        assert!(Role::Moderator > Role::User);
+        assert_eq!(Role::from_column(Role::Moderator.as_str()), Role::Moderator);
+    }
+
+    #[test]
+    fn session_without_role() {
+        let session: Session =
+            serde_json::from_str(r#"{"exp": "2024-01-24T18:30:00Z", "account_id": 3, "jti": "a"}"#)
+                .unwrap();
+        assert_eq!(session.role, Role::User);
+        assert!(!session.is_moderator());
+    }
+
+    #[test]
+    fn role_cannot_be_registered() {
+        let account: Account =
+            serde_json::from_str(r#"{"email": "a@b.c", "password": "pw", "role": "admin"}"#)
+                .unwrap();
+        assert_eq!(account.role, Role::User);
+    }
+
+    #[test]
+    fn validate_email() {
+        assert!(account("user@example.com").validate_email().is_ok());
+        assert!(account("first.last@mail.example.org")
+            .validate_email()
+            .is_ok());
+
+        for email in [
+            "",
+            "user",
+            "@example.com",
+            "user@",
+            "user@localhost",
+            "a@b@c.d",
+            "a b@c.d",
+            "a@.com",
+        ] {
+            assert!(account(email).validate_email().is_err(), "{}", email);
+        }
+    }
+}
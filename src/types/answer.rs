@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::attachment::Attachment;
+use crate::types::comment::Comment;
 use crate::types::question::QuestionId;
+use crate::types::reaction::ReactionCount;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
 pub struct AnswerId(pub i32);
@@ -10,6 +13,24 @@ pub struct Answer {
     pub id: AnswerId,
     pub content: String,
     pub question_id: QuestionId,
+    /// Whether the owner of the question marked this answer as the one that solved it
+    pub is_accepted: bool,
+    /// Sum of all up and down votes
+    pub score: i32,
+    /// Emoji reactions, only filled in by the routes reading answers
+    #[serde(default)]
+    pub reactions: Vec<ReactionCount>,
+    /// Uploaded files, only filled in by the routes reading answers
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+/// An answer together with the comments left on it
+#[derive(Serialize, Debug, Clone)]
+pub struct AnswerWithComments {
+    #[serde(flatten)]
+    pub answer: Answer,
+    pub comments: Vec<Comment>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -17,3 +38,14 @@ pub struct NewAnswer {
     pub content: String,
     pub question_id: QuestionId,
 }
+
+/// New content for an existing answer
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AnswerUpdate {
+    pub content: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct AcceptedAnswer {
+    pub answer_id: AnswerId,
+}
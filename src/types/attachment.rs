@@ -0,0 +1,92 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::account::AccountId;
+
+/// Content types attachments may have, checked against the sniffed type of the upload
+pub const ALLOWED_CONTENT_TYPES: [&str; 7] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "application/zip",
+    "text/plain",
+];
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct AttachmentId(pub i32);
+
+/// A screenshot, log or other file uploaded to a question or an answer
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Attachment {
+    pub id: AttachmentId,
+    pub file_name: String,
+    pub content_type: String,
+    /// Size in bytes
+    pub size: i64,
+    /// Where the file can be downloaded
+    pub url: String,
+    pub account_id: AccountId,
+    pub created_on: NaiveDateTime,
+    /// Key of the file in the storage backend
+    #[serde(skip)]
+    pub storage_key: String,
+}
+
+/// An uploaded file that was put into the storage backend
+#[derive(Debug, Clone)]
+pub struct NewAttachment {
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub storage_key: String,
+}
+
+/// Determines the content type from the data itself, whatever the client claims it is.
+/// Anything that is not a known binary format but valid UTF-8 without control characters
+/// (besides whitespace and the escapes of colored logs) counts as `text/plain`.
+/// Returns `None` for content types that are not allowed.
+pub fn sniff_content_type(data: &[u8]) -> Option<&'static str> {
+    let content_type = match infer::get(data) {
+        Some(kind) => kind.mime_type(),
+        None if is_text(data) => "text/plain",
+        None => return None,
+    };
+
+    ALLOWED_CONTENT_TYPES
+        .iter()
+        .find(|allowed| **allowed == content_type)
+        .copied()
+}
+
+fn is_text(data: &[u8]) -> bool {
+    std::str::from_utf8(data).is_ok()
+        && !data
+            .iter()
+            .any(|byte| byte.is_ascii_control() && !b"\t\n\r\x0C\x1B".contains(byte))
+}
+
+#[cfg(test)]
+mod attachment_tests {
+    use super::sniff_content_type;
+
+    #[test]
+    fn sniff_image() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+        assert_eq!(sniff_content_type(&png), Some("image/png"));
+    }
+
+    #[test]
+    fn sniff_log() {
+        let log = b"2023-12-01T10:00:00Z ERROR connection refused\n";
+        assert_eq!(sniff_content_type(log), Some("text/plain"));
+    }
+
+    #[test]
+    fn reject_executables() {
+        let elf = [0x7F, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0];
+        assert_eq!(sniff_content_type(&elf), None);
+        assert_eq!(sniff_content_type(&[0xFF, 0xFE, 0x00, 0xD8]), None);
+    }
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::Error;
+
+use crate::types::{account::AccountId, answer::AnswerId, question::QuestionId};
+
+/// Bounties can run for at most this many days
+pub const MAX_BOUNTY_DAYS: i32 = 30;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct BountyId(pub i32);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BountyStatus {
+    Open,
+    /// Paid out to `awarded_answer_id`
+    Awarded,
+    /// Ran out without any answer to award it to, the points are not refunded
+    Expired,
+}
+
+impl BountyStatus {
+    /// Value of the `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BountyStatus::Open => "open",
+            BountyStatus::Awarded => "awarded",
+            BountyStatus::Expired => "expired",
+        }
+    }
+}
+
+/// Points the owner of a question offers for a good answer
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bounty {
+    pub id: BountyId,
+    pub question_id: QuestionId,
+    pub account_id: AccountId,
+    pub amount: i32,
+    pub status: BountyStatus,
+    pub awarded_answer_id: Option<AnswerId>,
+    pub created_on: NaiveDateTime,
+    pub expires_on: NaiveDateTime,
+    pub closed_on: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NewBounty {
+    pub amount: i32,
+    /// After how many days the bounty goes to the highest scored answer, if any scored above zero
+    pub days: i32,
+}
+
+impl NewBounty {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.amount <= 0 {
+            return Err(Error::InvalidParameter(String::from(
+                "amount must be positive",
+            )));
+        }
+        if !(1..=MAX_BOUNTY_DAYS).contains(&self.days) {
+            return Err(Error::InvalidParameter(format!(
+                "days must be between 1 and {}",
+                MAX_BOUNTY_DAYS
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BountyAward {
+    pub answer_id: AnswerId,
+}
+
+#[cfg(test)]
+mod bounty_tests {
+    use super::NewBounty;
+
+    #[test]
+    fn bounty_limits() {
+        let valid = |amount, days| NewBounty { amount, days }.validate().is_ok();
+        assert!(valid(50, 7));
+        assert!(!valid(0, 7));
+        assert!(!valid(50, 0));
+        assert!(!valid(50, 31));
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{account::AccountId, answer::AnswerId, question::QuestionId};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct CommentId(pub i32);
+
+/// A short clarification left on a question or on an answer, exactly one of
+/// `question_id` and `answer_id` is set
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Comment {
+    pub id: CommentId,
+    pub content: String,
+    pub question_id: Option<QuestionId>,
+    pub answer_id: Option<AnswerId>,
+    pub account_id: AccountId,
+    pub created_on: NaiveDateTime,
+    pub updated_on: NaiveDateTime,
+}
+
+/// Content of a new comment, or the new content of an existing one
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NewComment {
+    pub content: String,
+}
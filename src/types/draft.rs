@@ -0,0 +1,77 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::Error;
+
+use crate::types::question::NewQuestion;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct DraftId(pub i32);
+
+/// A question that is still being written, only visible to its author
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Draft {
+    pub id: DraftId,
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub created_on: NaiveDateTime,
+    pub updated_on: NaiveDateTime,
+}
+
+/// The state of a draft as autosaved by the client, every field may still be missing
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct DraftContent {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+impl TryFrom<Draft> for NewQuestion {
+    type Error = Error;
+
+    /// A draft can only be published once it has a title and content
+    fn try_from(draft: Draft) -> Result<Self, Self::Error> {
+        match (draft.title, draft.content) {
+            (Some(title), Some(content)) if !title.is_empty() && !content.is_empty() => {
+                Ok(NewQuestion {
+                    title,
+                    content,
+                    tags: draft.tags,
+                })
+            }
+            _ => Err(Error::InvalidParameter(String::from(
+                "draft needs a title and content to be published",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod draft_tests {
+    use super::{Draft, DraftId, NewQuestion};
+
+    fn draft(title: Option<&str>, content: Option<&str>) -> Draft {
+        Draft {
+            id: DraftId(1),
+            title: title.map(String::from),
+            content: content.map(String::from),
+            tags: Some(vec![String::from("rust")]),
+            created_on: chrono::NaiveDateTime::default(),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn publish_complete_draft() {
+        let question = NewQuestion::try_from(draft(Some("How?"), Some("Please help!"))).unwrap();
+        assert_eq!(question.title, "How?");
+        assert_eq!(question.tags, Some(vec![String::from("rust")]));
+    }
+
+    #[test]
+    fn cannot_publish_incomplete_draft() {
+        assert!(NewQuestion::try_from(draft(Some("How?"), None)).is_err());
+        assert!(NewQuestion::try_from(draft(Some(""), Some("Please help!"))).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use handle_errors::Error;
+use std::collections::HashMap;
+
+/// How the requested tags have to match the tags of a question
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum TagMatch {
+    /// The question has at least one of the requested tags
+    #[default]
+    Any,
+    /// The question has all of the requested tags
+    All,
+}
+
+/// Tag filter that is getting extracted from query params
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagFilter {
+    /// The tags the questions are filtered by
+    pub tags: Vec<String>,
+    /// Whether a question needs any or all of the tags
+    pub mode: TagMatch,
+}
+
+/// Order in which the '/questions' route returns questions
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Newest,
+    #[default]
+    Oldest,
+    /// Questions with the latest answer (or the latest questions without answers) first
+    RecentlyActive,
+    MostAnswered,
+    HighestScored,
+}
+
+impl SortOrder {
+    /// The `ORDER BY` clause for this order, each one is backed by an index on `questions`
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            SortOrder::Newest => "created_on DESC, id DESC",
+            SortOrder::Oldest => "created_on, id",
+            SortOrder::RecentlyActive => "last_activity_on DESC, id DESC",
+            SortOrder::MostAnswered => "answer_count DESC, id DESC",
+            SortOrder::HighestScored => "score DESC, id DESC",
+        }
+    }
+
+    /// Cursors encode `(created_on, id)`, so they only work for orders by creation date
+    pub fn supports_cursor(&self) -> bool {
+        matches!(self, SortOrder::Newest | SortOrder::Oldest)
+    }
+}
+
+/// Filter questions by whether they got answers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnswerFilter {
+    Answered,
+    Unanswered,
+    /// The owner accepted one of the answers
+    Accepted,
+}
+
+/// Filters and ordering that can be applied to the '/questions' route on top of pagination
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct QuestionFilter {
+    pub tags: Option<TagFilter>,
+    pub answers: Option<AnswerFilter>,
+    /// Only questions with an open bounty
+    pub bounty: bool,
+    pub sort: SortOrder,
+}
+
+/// Extract filter query parameters from the '/questions' route
+/// # Example query
+/// '/questions?tags=rust,async&match=all&answers=unanswered&bounty=open&sort=newest'
+/// # Example usage
+/// ```rust
+/// use question_and_answer::types::filter::{extract_question_filter, TagMatch};
+/// use std::collections::HashMap;
+///
+/// let mut query = HashMap::new();
+/// query.insert("tags".to_string(), "rust,async".to_string());
+/// let f = extract_question_filter(&query).unwrap();
+/// assert_eq!(f.tags.unwrap().mode, TagMatch::Any);
+/// ```
+pub fn extract_question_filter(params: &HashMap<String, String>) -> Result<QuestionFilter, Error> {
+    let mode = match params.get("match").map(String::as_str) {
+        None | Some("any") => TagMatch::Any,
+        Some("all") => TagMatch::All,
+        Some(other) => {
+            return Err(Error::InvalidParameter(format!(
+                "match must be 'any' or 'all', got '{}'",
+                other
+            )))
+        }
+    };
+
+    let sort = match params.get("sort").map(String::as_str) {
+        None | Some("oldest") => SortOrder::Oldest,
+        Some("newest") => SortOrder::Newest,
+        Some("recently-active") => SortOrder::RecentlyActive,
+        Some("most-answered") => SortOrder::MostAnswered,
+        Some("highest-scored") => SortOrder::HighestScored,
+        Some(other) => {
+            return Err(Error::InvalidParameter(format!(
+                "sort must be one of 'newest', 'oldest', 'recently-active', 'most-answered' or 'highest-scored', got '{}'",
+                other
+            )))
+        }
+    };
+
+    let answers = match params.get("answers").map(String::as_str) {
+        None => None,
+        Some("answered") => Some(AnswerFilter::Answered),
+        Some("unanswered") => Some(AnswerFilter::Unanswered),
+        Some("accepted") => Some(AnswerFilter::Accepted),
+        Some(other) => {
+            return Err(Error::InvalidParameter(format!(
+                "answers must be 'answered', 'unanswered' or 'accepted', got '{}'",
+                other
+            )))
+        }
+    };
+
+    let bounty = match params.get("bounty").map(String::as_str) {
+        None => false,
+        Some("open") => true,
+        Some(other) => {
+            return Err(Error::InvalidParameter(format!(
+                "bounty must be 'open', got '{}'",
+                other
+            )))
+        }
+    };
+
+    let tags = params.get("tags").map(|tags| {
+        tags.split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect::<Vec<String>>()
+    });
+
+    Ok(QuestionFilter {
+        tags: match tags {
+            Some(tags) if !tags.is_empty() => Some(TagFilter { tags, mode }),
+            _ => None,
+        },
+        answers,
+        bounty,
+        sort,
+    })
+}
+
+#[cfg(test)]
+mod filter_tests {
+    use super::{
+        extract_question_filter, AnswerFilter, Error, HashMap, QuestionFilter, SortOrder,
+        TagFilter, TagMatch,
+    };
+
+    #[test]
+    fn no_filter() {
+        let params = HashMap::new();
+        let filter = extract_question_filter(&params).unwrap();
+        assert_eq!(filter, QuestionFilter::default());
+    }
+
+    #[test]
+    fn tags_default_to_any() {
+        let mut params = HashMap::new();
+        params.insert(String::from("tags"), String::from("rust, async,,"));
+        let filter = extract_question_filter(&params).unwrap();
+        let expected = TagFilter {
+            tags: vec![String::from("rust"), String::from("async")],
+            mode: TagMatch::Any,
+        };
+        assert_eq!(filter.tags.unwrap(), expected);
+    }
+
+    #[test]
+    fn tags_match_all() {
+        let mut params = HashMap::new();
+        params.insert(String::from("tags"), String::from("rust"));
+        params.insert(String::from("match"), String::from("all"));
+        let filter = extract_question_filter(&params).unwrap();
+        assert_eq!(filter.tags.unwrap().mode, TagMatch::All);
+    }
+
+    #[test]
+    fn wrong_match_value() {
+        let mut params = HashMap::new();
+        params.insert(String::from("tags"), String::from("rust"));
+        params.insert(String::from("match"), String::from("some"));
+        let filter_result = format!("{}", extract_question_filter(&params).unwrap_err());
+        let expected = format!(
+            "{}",
+            Error::InvalidParameter(String::from("match must be 'any' or 'all', got 'some'"))
+        );
+        assert_eq!(filter_result, expected);
+    }
+
+    #[test]
+    fn sort_order() {
+        let mut params = HashMap::new();
+        params.insert(String::from("sort"), String::from("most-answered"));
+        let filter = extract_question_filter(&params).unwrap();
+        assert_eq!(filter.sort, SortOrder::MostAnswered);
+    }
+
+    #[test]
+    fn unknown_sort_order() {
+        let mut params = HashMap::new();
+        params.insert(
+            String::from("sort"),
+            String::from("id; DROP TABLE questions"),
+        );
+        assert!(extract_question_filter(&params).is_err());
+    }
+
+    #[test]
+    fn answer_filter() {
+        let mut params = HashMap::new();
+        params.insert(String::from("answers"), String::from("accepted"));
+        let filter = extract_question_filter(&params).unwrap();
+        assert_eq!(filter.answers, Some(AnswerFilter::Accepted));
+    }
+
+    #[test]
+    fn bounty_filter() {
+        let mut params = HashMap::new();
+        params.insert(String::from("bounty"), String::from("open"));
+        assert!(extract_question_filter(&params).unwrap().bounty);
+
+        params.insert(String::from("bounty"), String::from("closed"));
+        assert!(extract_question_filter(&params).is_err());
+    }
+}
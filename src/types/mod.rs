@@ -1,4 +1,20 @@
 pub mod account;
 pub mod answer;
+pub mod attachment;
+pub mod bounty;
+pub mod comment;
+pub mod draft;
+pub mod filter;
 pub mod pagination;
+pub mod password_reset;
+pub mod points;
+pub mod post;
+pub mod profile;
 pub mod question;
+pub mod reaction;
+pub mod revision;
+pub mod search;
+pub mod session;
+pub mod suggested_edit;
+pub mod token;
+pub mod vote;
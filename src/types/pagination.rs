@@ -1,5 +1,7 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::NaiveDateTime;
 use handle_errors::Error;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Pagination struct that is getting extracted from query params
 #[derive(Default, Debug, PartialEq)]
@@ -8,22 +10,74 @@ pub struct Pagination {
     pub limit: Option<i32>,
     /// The index of the last item that has to be returned
     pub offset: i32,
+    /// The position after which the next page starts when paging with cursors
+    pub cursor: Option<Cursor>,
+}
+
+/// Position of a question in the `(created_on, id)` ordering, handed to clients
+/// as an opaque token so they can page without offsets
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    pub created_on: NaiveDateTime,
+    pub id: i32,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}:{}",
+            self.created_on.timestamp_micros(),
+            self.id
+        ))
+    }
+
+    pub fn decode(token: &str) -> Result<Cursor, Error> {
+        let invalid = || Error::InvalidParameter("cursor is not valid".to_string());
+
+        let decoded = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+        let (micros, id) = decoded.split_once(':').ok_or_else(invalid)?;
+
+        Ok(Cursor {
+            created_on: micros
+                .parse::<i64>()
+                .ok()
+                .and_then(NaiveDateTime::from_timestamp_micros)
+                .ok_or_else(invalid)?,
+            id: id.parse::<i32>().map_err(Error::ParseError)?,
+        })
+    }
 }
 
 /// Extract query parameters from the '/questions' route
 /// # Example query
 /// GET requests to this route can have pagination attached so we just return the questions we need
 /// '/questions?start=1&end=10'
+/// or, to continue after the last page that was returned, '/questions?limit=10&cursor=<next_cursor>'
 /// # Example usage
 /// ```rust
+/// use std::collections::HashMap;
+/// use question_and_answer::types::pagination;
+///
 /// let mut query = HashMap::new();
 /// query.insert("limit".to_string(), "1".to_string());
 /// query.insert("offset".to_string(), "10".to_string());
 /// let p = pagination::extract_pagination(query).unwrap();
-/// assert_eq!(p.limit,1);
+/// assert_eq!(p.limit, Some(1));
 /// assert_eq!(p.offset, 10);
 /// ```
 pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination, Error> {
+    if let Some(cursor) = params.get("cursor") {
+        return Ok(Pagination {
+            limit: params
+                .get("limit")
+                .map(|limit| limit.parse::<i32>().map_err(Error::ParseError))
+                .transpose()?,
+            offset: 0,
+            cursor: Some(Cursor::decode(cursor)?),
+        });
+    }
+
     // could be improved in the future
     if params.contains_key("limit") && params.contains_key("offset") {
         return Ok(Pagination {
@@ -47,6 +101,7 @@ pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination,
                 .unwrap()
                 .parse::<i32>()
                 .map_err(Error::ParseError)?,
+            cursor: None,
             // OLD CODE
             // end: params
             //     .get("end")
@@ -58,11 +113,73 @@ pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination,
     Err(Error::MissingParameters)
 }
 
+/// Builds the value of an RFC 8288 `Link` header pointing to the pages around the current one.
+/// All query parameters that are not about pagination (filters, sorting) are kept in the links.
+/// # Example header
+/// '</questions?limit=10&offset=20>; rel="next", </questions?limit=10&offset=0>; rel="first"'
+pub fn link_header(
+    path: &str,
+    params: &HashMap<String, String>,
+    pagination: &Pagination,
+    total: i64,
+    next_cursor: Option<&str>,
+) -> Option<String> {
+    let limit = match pagination.limit {
+        Some(limit) if limit > 0 => limit,
+        _ => return None,
+    };
+
+    let base: BTreeMap<&str, String> = params
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "limit" | "offset" | "cursor"))
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect();
+
+    let link = |position: (&'static str, String), rel: &str| {
+        let mut query = base.clone();
+        query.insert("limit", limit.to_string());
+        query.insert(position.0, position.1);
+        let query = serde_urlencoded::to_string(&query).unwrap_or_default();
+        format!("<{}?{}>; rel=\"{}\"", path, query, rel)
+    };
+
+    let mut links = Vec::new();
+
+    if pagination.cursor.is_some() {
+        // Cursors only move forward, so there is no previous or last page to link to
+        if let Some(next_cursor) = next_cursor {
+            links.push(link(("cursor", next_cursor.to_string()), "next"));
+        }
+        links.push(link(("offset", 0.to_string()), "first"));
+    } else {
+        let offset = pagination.offset;
+        let last = if total > 0 {
+            ((total - 1) / limit as i64) * limit as i64
+        } else {
+            0
+        };
+
+        if (offset as i64) + (limit as i64) < total {
+            links.push(link(("offset", (offset + limit).to_string()), "next"));
+        }
+        if offset > 0 {
+            let prev = (offset - limit).max(0);
+            links.push(link(("offset", prev.to_string()), "prev"));
+        }
+        links.push(link(("offset", 0.to_string()), "first"));
+        links.push(link(("offset", last.to_string()), "last"));
+    }
+
+    Some(links.join(", "))
+}
+
 #[cfg(test)]
 mod pagination_tests {
     // use std::hash::Hash;
 
-    use super::{extract_pagination, Error, HashMap, Pagination};
+    use super::{
+        extract_pagination, link_header, Cursor, Error, HashMap, NaiveDateTime, Pagination,
+    };
 
     #[test]
     fn valid_pagination() {
@@ -73,6 +190,7 @@ mod pagination_tests {
         let expected = Pagination {
             limit: Some(1),
             offset: 1,
+            cursor: None,
         };
         assert_eq!(pagination_result.unwrap(), expected);
     }
@@ -123,4 +241,83 @@ mod pagination_tests {
 
         assert_eq!(pagination_result, expected);
     }
+
+    #[test]
+    fn valid_cursor_pagination() {
+        let cursor = Cursor {
+            created_on: NaiveDateTime::from_timestamp_micros(1_692_000_000_123_456).unwrap(),
+            id: 42,
+        };
+        let mut params = HashMap::new();
+        params.insert(String::from("limit"), String::from("10"));
+        params.insert(String::from("cursor"), cursor.encode());
+        let pagination_result = extract_pagination(params);
+        let expected = Pagination {
+            limit: Some(10),
+            offset: 0,
+            cursor: Some(cursor),
+        };
+        assert_eq!(pagination_result.unwrap(), expected);
+    }
+
+    #[test]
+    fn invalid_cursor() {
+        let mut params = HashMap::new();
+        params.insert(String::from("cursor"), String::from("NOT A CURSOR"));
+        let pagination_result = format!("{}", extract_pagination(params).unwrap_err());
+
+        let expected = String::from("Invalid parameter: cursor is not valid");
+
+        assert_eq!(pagination_result, expected);
+    }
+
+    #[test]
+    fn links_for_middle_page() {
+        let mut params = HashMap::new();
+        params.insert(String::from("limit"), String::from("10"));
+        params.insert(String::from("offset"), String::from("10"));
+        params.insert(String::from("tags"), String::from("rust"));
+        let pagination = Pagination {
+            limit: Some(10),
+            offset: 10,
+            cursor: None,
+        };
+
+        let links = link_header("/questions", &params, &pagination, 35, None).unwrap();
+
+        let expected = [
+            r#"</questions?limit=10&offset=20&tags=rust>; rel="next""#,
+            r#"</questions?limit=10&offset=0&tags=rust>; rel="prev""#,
+            r#"</questions?limit=10&offset=0&tags=rust>; rel="first""#,
+            r#"</questions?limit=10&offset=30&tags=rust>; rel="last""#,
+        ]
+        .join(", ");
+        assert_eq!(links, expected);
+    }
+
+    #[test]
+    fn links_for_last_page() {
+        let pagination = Pagination {
+            limit: Some(10),
+            offset: 30,
+            cursor: None,
+        };
+
+        let links = link_header("/questions", &HashMap::new(), &pagination, 35, None).unwrap();
+
+        assert!(!links.contains(r#"rel="next""#));
+        assert!(links.contains(r#"</questions?limit=10&offset=20>; rel="prev""#));
+    }
+
+    #[test]
+    fn no_links_without_limit() {
+        let links = link_header(
+            "/questions",
+            &HashMap::new(),
+            &Pagination::default(),
+            35,
+            None,
+        );
+        assert_eq!(links, None);
+    }
 }
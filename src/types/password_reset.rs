@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+use handle_errors::Error;
+
+/// How long a reset token can be used after it was requested
+pub const RESET_TOKEN_MINUTES: i32 = 60;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PasswordForgot {
+    pub email: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct PasswordReset {
+    pub token: String,
+    pub password: String,
+}
+
+impl PasswordReset {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.password.is_empty() {
+            return Err(Error::InvalidParameter(String::from(
+                "password must not be empty",
+            )));
+        }
+        Ok(())
+    }
+}
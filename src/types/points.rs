@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::bounty::BountyId;
+
+/// Why the points of an account changed, stored in `points_ledger.reason`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerReason {
+    InitialGrant,
+    BountyOffered,
+    BountyAwarded,
+}
+
+impl LedgerReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerReason::InitialGrant => "initial_grant",
+            LedgerReason::BountyOffered => "bounty_offered",
+            LedgerReason::BountyAwarded => "bounty_awarded",
+        }
+    }
+}
+
+/// A single credit (positive `amount`) or debit (negative `amount`) of points
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LedgerEntry {
+    pub amount: i32,
+    pub reason: LedgerReason,
+    pub bounty_id: Option<BountyId>,
+    pub created_on: NaiveDateTime,
+}
+
+/// The points balance of an account and the entries it adds up from, latest first
+#[derive(Serialize, Debug, Clone)]
+pub struct Points {
+    pub balance: i32,
+    pub entries: Vec<LedgerEntry>,
+}
@@ -0,0 +1,25 @@
+use crate::types::{answer::AnswerId, question::QuestionId};
+
+/// A question or an answer, for everything that can be attached to either of them
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Post {
+    Question(QuestionId),
+    Answer(AnswerId),
+}
+
+impl Post {
+    /// The column referencing the post in tables that attach to questions and answers
+    pub fn column(&self) -> &'static str {
+        match self {
+            Post::Question(_) => "question_id",
+            Post::Answer(_) => "answer_id",
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        match self {
+            Post::Question(id) => id.0,
+            Post::Answer(id) => id.0,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::Error;
+
+use crate::types::{account::AccountId, answer::Answer, question::Question};
+
+/// The public part of an account, without its email or password
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Profile {
+    pub id: AccountId,
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    /// Url of the avatar image, e.g. an attachment or an external image
+    pub avatar_url: Option<String>,
+    pub joined_on: NaiveDateTime,
+}
+
+/// Replaces the editable fields of a profile, missing fields are cleared
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ProfileUpdate {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl ProfileUpdate {
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some(name) = &self.display_name {
+            if name.trim().is_empty() || name.chars().count() > 64 {
+                return Err(Error::InvalidParameter(String::from(
+                    "display_name must have between 1 and 64 characters",
+                )));
+            }
+        }
+        if let Some(url) = &self.avatar_url {
+            let allowed = url.starts_with("https://")
+                || url.starts_with("http://")
+                || url.starts_with("/attachments/");
+            if !allowed || url.len() > 2048 {
+                return Err(Error::InvalidParameter(String::from(
+                    "avatar_url must be an http(s) url or an attachment",
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Profile page of an account with the latest questions and answers it posted
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfileWithActivity {
+    #[serde(flatten)]
+    pub profile: Profile,
+    pub questions: Vec<Question>,
+    pub answers: Vec<Answer>,
+}
+
+#[cfg(test)]
+mod profile_tests {
+    use super::ProfileUpdate;
+
+    #[test]
+    fn valid_profile() {
+        let update = ProfileUpdate {
+            display_name: Some(String::from("Ferris")),
+            bio: Some(String::from("Crab")),
+            avatar_url: Some(String::from("/attachments/4")),
+        };
+        assert!(update.validate().is_ok());
+        assert!(ProfileUpdate::default().validate().is_ok());
+    }
+
+    #[test]
+    fn invalid_avatar() {
+        let update = ProfileUpdate {
+            avatar_url: Some(String::from("javascript:alert(1)")),
+            ..ProfileUpdate::default()
+        };
+        assert!(update.validate().is_err());
+    }
+
+    #[test]
+    fn blank_display_name() {
+        let update = ProfileUpdate {
+            display_name: Some(String::from("  ")),
+            ..ProfileUpdate::default()
+        };
+        assert!(update.validate().is_err());
+    }
+}
@@ -1,4 +1,10 @@
-use serde::{Deserialize, Serialize};
+use chrono::NaiveDateTime;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+
+use crate::types::answer::{Answer, AnswerId, AnswerWithComments};
+use crate::types::attachment::Attachment;
+use crate::types::comment::Comment;
+use crate::types::reaction::ReactionCount;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Question {
@@ -6,6 +12,38 @@ pub struct Question {
     pub title: String,
     pub content: String,
     pub tags: Option<Vec<String>>,
+    pub status: QuestionStatus,
+    pub accepted_answer_id: Option<AnswerId>,
+    /// Sum of all up and down votes
+    pub score: i32,
+    /// Emoji reactions, only filled in by the routes reading questions
+    #[serde(default)]
+    pub reactions: Vec<ReactionCount>,
+    /// Uploaded files, only filled in by the routes reading questions
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+}
+
+/// Where a question is in its lifecycle, e.g. `{"state": "closed", "reason": "off-topic"}`.
+/// Only open questions accept new answers and edits.
+#[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum QuestionStatus {
+    #[default]
+    Open,
+    Closed {
+        reason: String,
+    },
+    Duplicate {
+        of: QuestionId,
+    },
+    Locked,
+}
+
+impl QuestionStatus {
+    pub fn is_open(&self) -> bool {
+        matches!(self, QuestionStatus::Open)
+    }
 }
 #[derive(Debug, Serialize, Eq, PartialEq, Hash, Clone, Deserialize)]
 pub struct QuestionId(pub i32);
@@ -16,3 +54,186 @@ pub struct NewQuestion {
     pub content: String,
     pub tags: Option<Vec<String>>,
 }
+
+/// A single question together with all of the answers given to it, and the
+/// comments left on each of them
+#[derive(Serialize, Debug, Clone)]
+pub struct QuestionWithAnswers {
+    #[serde(flatten)]
+    pub question: Question,
+    pub comments: Vec<Comment>,
+    pub answers: Vec<AnswerWithComments>,
+}
+
+impl QuestionWithAnswers {
+    /// Hands every comment to the question or answer it was left on, keeping their order
+    pub fn new(question: Question, answers: Vec<Answer>, comments: Vec<Comment>) -> Self {
+        let (comments, answer_comments): (Vec<Comment>, Vec<Comment>) = comments
+            .into_iter()
+            .partition(|comment| comment.answer_id.is_none());
+
+        let answers = answers
+            .into_iter()
+            .map(|answer| {
+                let comments = answer_comments
+                    .iter()
+                    .filter(|comment| comment.answer_id.as_ref() == Some(&answer.id))
+                    .cloned()
+                    .collect();
+                AnswerWithComments { answer, comments }
+            })
+            .collect();
+
+        QuestionWithAnswers {
+            question,
+            comments,
+            answers,
+        }
+    }
+}
+
+/// A page of questions, with the token to request the page after it
+#[derive(Serialize, Debug, Clone)]
+pub struct QuestionPage {
+    pub items: Vec<Question>,
+    /// Number of questions matching the filters across all pages
+    pub total: i64,
+    pub limit: Option<i32>,
+    /// Not set when the page was requested with a cursor
+    pub offset: Option<i32>,
+    /// Opaque cursor to pass as `cursor` to get the next page, if there might be one
+    pub next_cursor: Option<String>,
+}
+
+/// A question in the trash of its owner
+#[derive(Serialize, Debug, Clone)]
+pub struct DeletedQuestion {
+    #[serde(flatten)]
+    pub question: Question,
+    pub deleted_at: NaiveDateTime,
+}
+
+/// Changes to a question following JSON Merge Patch (RFC 7396) semantics:
+/// fields that are left out stay as they are and `"tags": null` removes the tags.
+/// Title and content are required, so they cannot be set to `null`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct QuestionPatch {
+    #[serde(default, deserialize_with = "deserialize_not_null")]
+    pub title: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_not_null")]
+    pub content: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub tags: Option<Option<Vec<String>>>,
+}
+
+/// Keeps an explicit `null` (`Some(None)`) apart from a missing field (`None`)
+fn deserialize_nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+fn deserialize_not_null<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    match Option::<T>::deserialize(deserializer)? {
+        Some(value) => Ok(Some(value)),
+        None => Err(D::Error::custom("title and content cannot be removed")),
+    }
+}
+
+#[cfg(test)]
+mod question_tests {
+    use super::{Question, QuestionId, QuestionPatch, QuestionStatus, QuestionWithAnswers};
+    use crate::types::account::AccountId;
+    use crate::types::answer::{Answer, AnswerId};
+    use crate::types::comment::{Comment, CommentId};
+
+    #[test]
+    fn patch_missing_fields_stay_unchanged() {
+        let patch: QuestionPatch = serde_json::from_str(r#"{"tags": ["rust"]}"#).unwrap();
+        let expected = QuestionPatch {
+            title: None,
+            content: None,
+            tags: Some(Some(vec![String::from("rust")])),
+        };
+        assert_eq!(patch, expected);
+    }
+
+    #[test]
+    fn patch_null_removes_tags() {
+        let patch: QuestionPatch = serde_json::from_str(r#"{"tags": null}"#).unwrap();
+        assert_eq!(patch.tags, Some(None));
+    }
+
+    #[test]
+    fn patch_cannot_remove_title() {
+        let patch = serde_json::from_str::<QuestionPatch>(r#"{"title": null}"#);
+        assert!(patch.is_err());
+    }
+
+    #[test]
+    fn status_format() {
+        let status: QuestionStatus =
+            serde_json::from_str(r#"{"state": "duplicate", "of": 3}"#).unwrap();
+        assert_eq!(status, QuestionStatus::Duplicate { of: QuestionId(3) });
+        assert!(!status.is_open());
+
+        let status = serde_json::to_string(&QuestionStatus::Open).unwrap();
+        assert_eq!(status, r#"{"state":"open"}"#);
+    }
+
+    fn comment(id: i32, question_id: Option<i32>, answer_id: Option<i32>) -> Comment {
+        Comment {
+            id: CommentId(id),
+            content: String::from("Could you add the error message?"),
+            question_id: question_id.map(QuestionId),
+            answer_id: answer_id.map(AnswerId),
+            account_id: AccountId(1),
+            created_on: chrono::NaiveDateTime::default(),
+            updated_on: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn comments_go_to_their_post() {
+        let question = Question {
+            id: QuestionId(1),
+            title: String::from("How?"),
+            content: String::from("Please help!"),
+            tags: None,
+            status: QuestionStatus::Open,
+            accepted_answer_id: None,
+            score: 0,
+            reactions: Vec::new(),
+            attachments: Vec::new(),
+        };
+        let answers = [2, 3]
+            .into_iter()
+            .map(|id| Answer {
+                id: AnswerId(id),
+                content: String::from("Like this."),
+                question_id: QuestionId(1),
+                is_accepted: false,
+                score: 0,
+                reactions: Vec::new(),
+                attachments: Vec::new(),
+            })
+            .collect();
+        let comments = vec![
+            comment(1, None, Some(3)),
+            comment(2, Some(1), None),
+            comment(3, None, Some(3)),
+        ];
+
+        let view = QuestionWithAnswers::new(question, answers, comments);
+        let ids = |comments: &[Comment]| comments.iter().map(|c| c.id.0).collect::<Vec<_>>();
+        assert_eq!(ids(&view.comments), vec![2]);
+        assert_eq!(ids(&view.answers[0].comments), Vec::<i32>::new());
+        assert_eq!(ids(&view.answers[1].comments), vec![1, 3]);
+    }
+}
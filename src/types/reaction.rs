@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use handle_errors::Error;
+
+/// The fixed set of emoji accounts can react to posts with
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Reaction {
+    #[serde(rename = "thanks")]
+    Thanks,
+    #[serde(rename = "+1")]
+    PlusOne,
+    #[serde(rename = "eyes")]
+    Eyes,
+    #[serde(rename = "confused")]
+    Confused,
+}
+
+impl Reaction {
+    /// Value of the `reaction` column and of the path segment in the reaction routes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reaction::Thanks => "thanks",
+            Reaction::PlusOne => "+1",
+            Reaction::Eyes => "eyes",
+            Reaction::Confused => "confused",
+        }
+    }
+}
+
+impl FromStr for Reaction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "thanks" => Ok(Reaction::Thanks),
+            "+1" => Ok(Reaction::PlusOne),
+            "eyes" => Ok(Reaction::Eyes),
+            "confused" => Ok(Reaction::Confused),
+            other => Err(Error::InvalidParameter(format!(
+                "reaction must be 'thanks', '+1', 'eyes' or 'confused', got '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+/// How often a post got a reaction, and whether the logged in account is one of them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReactionCount {
+    pub reaction: Reaction,
+    pub count: i64,
+    pub reacted: bool,
+}
+
+#[cfg(test)]
+mod reaction_tests {
+    use super::Reaction;
+
+    #[test]
+    fn reaction_names() {
+        for reaction in [
+            Reaction::Thanks,
+            Reaction::PlusOne,
+            Reaction::Eyes,
+            Reaction::Confused,
+        ] {
+            assert_eq!(reaction.as_str().parse::<Reaction>().unwrap(), reaction);
+            let json = serde_json::to_string(&reaction).unwrap();
+            assert_eq!(json, format!("\"{}\"", reaction.as_str()));
+        }
+        assert!("heart".parse::<Reaction>().is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::types::{account::AccountId, question::QuestionId};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct RevisionId(pub i32);
+
+/// State of a question after it was created or edited
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Revision {
+    pub id: RevisionId,
+    pub question_id: QuestionId,
+    pub title: String,
+    pub content: String,
+    pub tags: Option<Vec<String>>,
+    /// The account that made this edit
+    pub account_id: AccountId,
+    pub created_on: NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TitleChange {
+    pub from: String,
+    pub to: String,
+}
+
+/// Differences between two revisions of the same question
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RevisionDiff {
+    pub from: RevisionId,
+    pub to: RevisionId,
+    pub title: Option<TitleChange>,
+    /// Line based diff of the content in unified format, empty if the content did not change
+    pub content: String,
+    pub tags_added: Vec<String>,
+    pub tags_removed: Vec<String>,
+}
+
+impl Revision {
+    /// Describes the changes needed to get from this revision to `to`
+    pub fn diff(&self, to: &Revision) -> RevisionDiff {
+        let from_tags = self.tags.clone().unwrap_or_default();
+        let to_tags = to.tags.clone().unwrap_or_default();
+
+        RevisionDiff {
+            from: self.id.clone(),
+            to: to.id.clone(),
+            title: match self.title == to.title {
+                true => None,
+                false => Some(TitleChange {
+                    from: self.title.clone(),
+                    to: to.title.clone(),
+                }),
+            },
+            content: TextDiff::from_lines(&self.content, &to.content)
+                .unified_diff()
+                .header(
+                    &format!("revision {}", self.id.0),
+                    &format!("revision {}", to.id.0),
+                )
+                .to_string(),
+            tags_added: to_tags
+                .iter()
+                .filter(|tag| !from_tags.contains(tag))
+                .cloned()
+                .collect(),
+            tags_removed: from_tags
+                .iter()
+                .filter(|tag| !to_tags.contains(tag))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod revision_tests {
+    use super::{AccountId, NaiveDateTime, QuestionId, Revision, RevisionId, TitleChange};
+
+    fn revision(id: i32, title: &str, content: &str, tags: &[&str]) -> Revision {
+        Revision {
+            id: RevisionId(id),
+            question_id: QuestionId(1),
+            title: title.to_string(),
+            content: content.to_string(),
+            tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+            account_id: AccountId(1),
+            created_on: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn diff_between_revisions() {
+        let from = revision(1, "How?", "first line\nsecond line\n", &["rust", "faq"]);
+        let to = revision(
+            2,
+            "How to test?",
+            "first line\nchanged line\n",
+            &["rust", "testing"],
+        );
+
+        let diff = from.diff(&to);
+
+        assert_eq!(
+            diff.title,
+            Some(TitleChange {
+                from: "How?".to_string(),
+                to: "How to test?".to_string(),
+            })
+        );
+        assert!(diff.content.contains("-second line"));
+        assert!(diff.content.contains("+changed line"));
+        assert_eq!(diff.tags_added, vec!["testing".to_string()]);
+        assert_eq!(diff.tags_removed, vec!["faq".to_string()]);
+    }
+
+    #[test]
+    fn diff_without_changes() {
+        let from = revision(1, "How?", "content\n", &["rust"]);
+        let to = revision(2, "How?", "content\n", &["rust"]);
+
+        let diff = from.diff(&to);
+
+        assert_eq!(diff.title, None);
+        assert!(diff.content.is_empty());
+        assert!(diff.tags_added.is_empty());
+        assert!(diff.tags_removed.is_empty());
+    }
+}
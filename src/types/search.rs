@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::{answer::AnswerId, question::QuestionId};
+
+/// A question matching a full-text search, ranked by relevance.
+/// `highlighted_title` and `snippet` are HTML, the user's text is escaped and matched terms
+/// are wrapped in `<mark>` tags.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchResult {
+    pub question_id: QuestionId,
+    pub title: String,
+    pub highlighted_title: String,
+    pub snippet: String,
+    /// Set when the snippet was taken from an answer instead of the question itself
+    pub answer_id: Option<AnswerId>,
+    pub rank: f32,
+}
@@ -0,0 +1,36 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Lifetime of access tokens, short since they can only be revoked through the revocation list
+pub const ACCESS_TOKEN_MINUTES: i32 = 15;
+/// Lifetime of a login, refreshing rotates the refresh token but does not extend it
+pub const REFRESH_TOKEN_DAYS: i32 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionId(pub i32);
+
+/// A login of an account, kept alive by its refresh token
+#[derive(Serialize, Debug, Clone)]
+pub struct ActiveSession {
+    pub id: SessionId,
+    pub user_agent: Option<String>,
+    pub created_on: NaiveDateTime,
+    pub last_used_on: NaiveDateTime,
+    pub expires_on: NaiveDateTime,
+    /// Whether the request listing the sessions was made with this one
+    pub current: bool,
+}
+
+/// Returned by login and refresh, the refresh token can only be used once
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Seconds until the access token expires
+    pub expires_in: i64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{account::AccountId, answer::AnswerId, post::Post, question::QuestionId};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
+pub struct SuggestedEditId(pub i32);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl EditStatus {
+    /// Value of the `status` column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EditStatus::Pending => "pending",
+            EditStatus::Approved => "approved",
+            EditStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// A change to somebody else's question or answer, waiting for its owner to review it.
+/// `title` and `tags` are only used for questions.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SuggestedEdit {
+    pub id: SuggestedEditId,
+    pub question_id: Option<QuestionId>,
+    pub answer_id: Option<AnswerId>,
+    /// The account suggesting the edit
+    pub account_id: AccountId,
+    pub title: Option<String>,
+    pub content: String,
+    pub tags: Option<Vec<String>>,
+    pub status: EditStatus,
+    pub reviewer_id: Option<AccountId>,
+    pub created_on: NaiveDateTime,
+    pub reviewed_on: Option<NaiveDateTime>,
+}
+
+impl SuggestedEdit {
+    /// The question or answer the edit applies to
+    pub fn post(&self) -> Post {
+        match (&self.question_id, &self.answer_id) {
+            (Some(id), _) => Post::Question(id.clone()),
+            (None, Some(id)) => Post::Answer(id.clone()),
+            (None, None) => unreachable!("suggested edits reference a question or an answer"),
+        }
+    }
+}
+
+/// A reviewer's improved version of a suggested edit, replacing what was suggested
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SuggestedEditUpdate {
+    pub title: Option<String>,
+    pub content: String,
+    pub tags: Option<Vec<String>>,
+}
+
+#[cfg(test)]
+mod suggested_edit_tests {
+    use super::{EditStatus, SuggestedEdit, SuggestedEditId};
+    use crate::types::{account::AccountId, answer::AnswerId, post::Post};
+
+    #[test]
+    fn edit_to_answer() {
+        let edit = SuggestedEdit {
+            id: SuggestedEditId(1),
+            question_id: None,
+            answer_id: Some(AnswerId(4)),
+            account_id: AccountId(2),
+            title: None,
+            content: String::from("Fixed the typo."),
+            tags: None,
+            status: EditStatus::Pending,
+            reviewer_id: None,
+            created_on: chrono::NaiveDateTime::default(),
+            reviewed_on: None,
+        };
+        assert_eq!(edit.post(), Post::Answer(AnswerId(4)));
+
+        let status = serde_json::to_string(&edit.status).unwrap();
+        assert_eq!(status, format!("\"{}\"", EditStatus::Pending.as_str()));
+    }
+}
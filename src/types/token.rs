@@ -0,0 +1,37 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// A random opaque token, like password reset or refresh tokens, only its hash is stored
+pub fn generate_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::thread_rng().gen::<[u8; 32]>())
+}
+
+/// Hex encoded SHA-256 of the token, what gets stored and looked up
+///
+/// The tokens are random, so unlike passwords they need no salt or slow hash
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod token_tests {
+    use super::{generate_token, hash_token};
+
+    #[test]
+    fn tokens_are_random() {
+        assert_ne!(generate_token(), generate_token());
+        assert_eq!(generate_token().len(), 43);
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        let token = generate_token();
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+        assert_eq!(
+            hash_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+/// An up (`1`) or down (`-1`) vote
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct NewVote {
+    pub value: i16,
+}